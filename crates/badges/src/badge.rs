@@ -8,7 +8,10 @@ use crate::MintRule;
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Badge {
     /// Account who has the authority to edit the badge's info.
-    pub manager: Addr,
+    ///
+    /// Set to None once the manager has renounced their power, in which case the badge's info can
+    /// no longer be edited by anyone.
+    pub manager: Option<Addr>,
 
     /// The badge's metadata
     pub metadata: Metadata,
@@ -19,10 +22,18 @@ pub struct Badge {
     /// The rule by which instances of this badge are to be minted
     pub rule: MintRule,
 
-    /// The timestamp only before which the badge can be minted
+    /// The timestamp only after which the badge can be minted
+    pub start: Option<u64>,
+
+    /// The timestamp only before which the badge can be minted. Can be extended by the manager.
     pub expiry: Option<u64>,
 
-    /// The maximum number of badge instances can be minted
+    /// If not empty, the badge can only be minted during one of these phases, in addition to the
+    /// `start` and `expiry` constraints above
+    #[serde(default)]
+    pub phases: Vec<Phase>,
+
+    /// The maximum number of badge instances can be minted. Can be lowered by the manager.
     pub max_supply: Option<u64>,
 
     /// The current number of this badge
@@ -31,4 +42,89 @@ pub struct Badge {
     /// of tokens that can ever be minted. A user burning their tokens does not make room for new
     /// tokens to be minted.
     pub current_supply: u64,
+
+    /// Whether minting has been paused by the manager, e.g. because a claim key has leaked.
+    ///
+    /// Pausing does not make a badge unavailable: its keys and owners can only be purged once it
+    /// has expired or sold out, regardless of whether it is paused.
+    #[serde(default)]
+    pub paused: bool,
+
+    /// The format of the message to be signed to claim the badge under the "by key" or "by keys"
+    /// rule. Badges created before the v2 format was introduced use the legacy format; new badges
+    /// use the v2 format unless specified otherwise.
+    #[serde(default)]
+    pub message_version: MessageVersion,
+
+    /// The maximum number of instances of this badge each user can claim. None means unlimited.
+    ///
+    /// Badges created before this setting was introduced can be claimed once per user. The minter
+    /// of a badge under the "by minter" rule is not subject to this limit. Badges whose rule has
+    /// reusable proofs, i.e. "by key", must be limited to one claim per user. Under the "by merkle
+    /// root" rule, the amount in each user's leaf is enforced in addition to this limit.
+    #[serde(default = "default_max_per_user")]
+    pub max_per_user: Option<u64>,
+
+    /// Whether instances minted by the minter under the "by minter" rule must be accepted by their
+    /// recipients. If true, minting creates pending offers instead, which each recipient can accept
+    /// to receive the badge, or reject. Offers can no longer be accepted once the badge becomes
+    /// unavailable.
+    ///
+    /// This is useful for non-transferrable badges, which the recipient can't get rid of otherwise.
+    #[serde(default)]
+    pub require_consent: bool,
+}
+
+pub(crate) fn default_max_per_user() -> Option<u64> {
+    Some(1)
+}
+
+pub(crate) fn default_message_version() -> MessageVersion {
+    MessageVersion::V2
+}
+
+/// The format of the claim message signed under the "by key" or "by keys" rule
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageVersion {
+    /// `claim badge {id} for user {user}`
+    ///
+    /// NOTE: The message does not specify the chain or the Hub contract, so a signature can be
+    /// replayed on another chain or another Hub deployment, for a badge of the same id signed with
+    /// the same key.
+    #[default]
+    Legacy,
+
+    /// The message additionally specifies the chain id, the Hub contract address, and optionally a
+    /// deadline after which the signature is no longer accepted.
+    ///
+    /// Use the `claim_message` query to get the exact message to be signed.
+    V2,
+}
+
+/// A time window during which a badge can be minted, optionally with its own supply cap.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Phase {
+    /// The timestamp at which this phase starts
+    pub start: u64,
+
+    /// The timestamp after which this phase ends. None means this phase never ends, which is only
+    /// allowed for the last phase.
+    pub expiry: Option<u64>,
+
+    /// The maximum total supply of the badge by the end of this phase, including instances minted
+    /// during earlier phases. None means this phase has no supply cap of its own.
+    pub max_supply: Option<u64>,
+}
+
+impl Phase {
+    /// Whether the phase is ongoing at the given timestamp
+    pub fn is_active(&self, timestamp: u64) -> bool {
+        timestamp >= self.start && !self.has_ended(timestamp)
+    }
+
+    /// Whether the phase has ended at the given timestamp
+    pub fn has_ended(&self, timestamp: u64) -> bool {
+        matches!(self.expiry, Some(expiry) if timestamp > expiry)
+    }
 }
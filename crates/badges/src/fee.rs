@@ -10,4 +10,9 @@ pub struct FeeRate {
 
     /// The fee rate, in ustars per byte, for storing claim keys on-chain
     pub key: Decimal,
+
+    /// The fraction of the price that is charged as a protocol fee, through Stargaze's fair burn
+    /// mechanism, when a badge is minted by payment. Only applies to badges priced in ustars.
+    #[serde(default)]
+    pub mint: Decimal,
 }
@@ -1,10 +1,11 @@
 use std::collections::BTreeSet;
 
+use cosmwasm_std::Binary;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sg_metadata::Metadata;
 
-use crate::{Badge, FeeRate, MintRule};
+use crate::{Badge, FeeRate, KeyInfo, KeyType, MessageVersion, MintProof, MintRule, Phase};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct InstantiateMsg {
@@ -20,6 +21,15 @@ pub enum SudoMsg {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
+pub struct MigrateMsg {
+    /// The maximum number of badges and keys to convert in this call. If the migration isn't
+    /// complete after that, the contract stays at the old version, and the migration can be
+    /// invoked again to pick up where it left off. Defaults to 100; must not be zero.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 #[allow(clippy::large_enum_variant)]
@@ -29,11 +39,10 @@ pub enum ExecuteMsg {
         /// Manager is the account that can 1) change the badge's metadata, and 2) if using the "by
         /// keys" mint rule, whitelist pubkeys.
         ///
-        /// TODO: Make mananger an optional parameter; setting it to None meaning no one can change
-        /// the metadata. Also, allow transferring of manager power in the `edit_badge` method.
-        ///
-        /// NOTE: If using the "by keys" minting rule, manager cannot be None, because a manager is
-        /// is needed to whitelist keys.
+        /// The manager power can later be handed over to another account using the
+        /// `transfer_manager` and `accept_manager` methods, or given up using `renounce_manager`.
+        /// Renouncing the manager of a badge using the "by keys" mint rule permanently freezes its
+        /// set of whitelisted keys, as no one can add or revoke keys afterwards.
         manager: String,
         /// The badge's metadata, defined by the OpenSea standard
         metadata: Metadata,
@@ -42,15 +51,37 @@ pub enum ExecuteMsg {
         /// The rule by which this badge is to be minted. There are three available rules; see the
         /// docs of `badges::MintRule` for details.
         rule: MintRule,
+        /// A timestamp only after which the badge can be minted.
+        /// Setting this to None means minting starts right away.
+        start: Option<u64>,
         /// A deadline only before which the badge can be minted.
         /// Setting this to None means there is no deadline.
-        /// Can only be set once when creating the badge; cannot be changed later.
+        /// Can only be extended later, using the `edit_badge_config` method.
         expiry: Option<u64>,
+        /// Optional minting phases, each with its own time window and supply cap. If provided, the
+        /// badge can only be minted during one of the phases. Phases must be sorted by time and
+        /// must not overlap; only the last phase may have no expiry.
+        #[serde(default)]
+        phases: Vec<Phase>,
         /// The maximum amount of badge that can be minted. Note, users burning minted badges does
         /// NOT free up slots for new badges to be minted.
         /// Setting this to None means there is no max supply.
-        /// Can only be set once when creating the badge; cannot be changed later.
+        /// Can only be lowered later, using the `edit_badge_config` method.
         max_supply: Option<u64>,
+        /// The format of the message to be signed to claim the badge under the "by key" or "by
+        /// keys" rule. Defaults to the v2 format, which prevents signatures from being replayed on
+        /// other chains or Hub deployments. The legacy format is only kept for compatibility.
+        #[serde(default = "crate::badge::default_message_version")]
+        message_version: MessageVersion,
+        /// The maximum number of instances of this badge each user can claim. Defaults to 1.
+        /// Setting this to None means there is no limit. Must not be zero, and must be 1 if the
+        /// mint rule is, or contains, "by key", whose signatures could otherwise be replayed.
+        #[serde(default = "crate::badge::default_max_per_user")]
+        max_per_user: Option<u64>,
+        /// Whether instances minted by the minter must be accepted by their recipients. Defaults
+        /// to false.
+        #[serde(default)]
+        require_consent: bool,
     },
 
     /// Edit the metadata of an existing badge; only the manager can call
@@ -59,6 +90,19 @@ pub enum ExecuteMsg {
         metadata: Metadata,
     },
 
+    /// For a badge that uses the "by key" mint rule, replace the pubkey with a new one, e.g. if the
+    /// private key has leaked. Only callable by the manager before the minting deadline or max
+    /// supply has been reached.
+    ///
+    /// Users who have already claimed the badge can't claim it again using the new key. The new key
+    /// is charged the key fee in full.
+    RotateKey {
+        id: u64,
+        key: String,
+        #[serde(default)]
+        key_type: KeyType,
+    },
+
     /// For a badge that uses the "by keys" mint rule, invoke this method to whitelist pubkeys.
     /// Only callable by the manager before the minting deadline or max supply has been reached.
     AddKeys {
@@ -66,6 +110,82 @@ pub enum ExecuteMsg {
         /// NOTE: Use BTreeSet, because the order of items in a HashSet may not be deterministic.
         /// See: https://www.reddit.com/r/rust/comments/krgvcu/is_the_iteration_order_of_hashset_deterministic/
         keys: BTreeSet<String>,
+        /// The type of the keys. To whitelist keys of different types, add them in separate
+        /// batches.
+        #[serde(default)]
+        key_type: KeyType,
+        /// The number of times each key can be used to mint. Defaults to 1.
+        uses: Option<u64>,
+        /// The timestamp only before which the keys can be used. Defaults to no expiry.
+        expiry: Option<u64>,
+        /// A label or tier, stamped as the `tier` trait on badge instances minted using the keys
+        tier: Option<String>,
+    },
+
+    /// Edit the minting deadline, max supply, or transferrability of an existing badge; only the
+    /// manager can call. Fields set to None are left unchanged.
+    ///
+    /// To make sure the manager can't change the terms under which users claimed the badge, these
+    /// can only be edited in one direction:
+    /// - the deadline can only be extended, and only before it has been reached;
+    /// - the max supply can only be lowered, and not below the current supply;
+    /// - a transferrable badge can be made non-transferrable, but not the other way around.
+    EditBadgeConfig {
+        id: u64,
+        expiry: Option<u64>,
+        max_supply: Option<u64>,
+        transferrable: Option<bool>,
+    },
+
+    /// Pause or resume the minting of a badge; only the manager can call.
+    ///
+    /// This is meant as an emergency stop, for example if the private key of a badge using the "by
+    /// key" mint rule has leaked. While paused, all minting methods are rejected. Pausing does not
+    /// make the badge unavailable, so its data can't be purged unless it has also expired or sold
+    /// out.
+    SetPaused {
+        id: u64,
+        paused: bool,
+    },
+
+    /// Propose a new manager for a badge; only the current manager can call.
+    ///
+    /// The handover only takes effect once the new manager accepts it using the `accept_manager`
+    /// method. Proposing again overwrites the previous proposal.
+    TransferManager {
+        id: u64,
+        new_manager: String,
+    },
+
+    /// Accept a manager handover proposed by the current manager; only the proposed new manager can
+    /// call.
+    AcceptManager {
+        id: u64,
+    },
+
+    /// Give up the manager power of a badge; only the manager can call.
+    ///
+    /// This is irreversible. Once renounced, no one can edit the badge's metadata or whitelist keys.
+    RenounceManager {
+        id: u64,
+    },
+
+    /// For a badge that uses the "by keys" mint rule, remove the specified pubkeys from the
+    /// whitelist, e.g. if a batch of printed claim cards has been lost. Only callable by the
+    /// manager. Keys that are not whitelisted (including ones that have already been used) are
+    /// ignored.
+    RevokeKeys {
+        id: u64,
+        /// NOTE: Use BTreeSet instead of HashSet, the same reason as discussed above
+        keys: BTreeSet<String>,
+    },
+
+    /// For a badge that uses the "by keys" mint rule, remove all whitelisted pubkeys. Only callable
+    /// by the manager. If there are too many keys to be removed in one transaction, invoke this
+    /// method multiple times with a `limit`.
+    RevokeAllKeys {
+        id: u64,
+        limit: Option<u32>,
     },
 
     /// Once a badge has expired or sold out, the whitelisted keys are no longer needed. Invoke this
@@ -75,6 +195,31 @@ pub enum ExecuteMsg {
         limit: Option<u32>,
     },
 
+    /// For a badge that uses the "by allowlist" mint rule, invoke this method to add addresses to
+    /// the allowlist. Only callable by the manager before the minting deadline or max supply has
+    /// been reached.
+    AddAllowlist {
+        id: u64,
+        /// NOTE: Use BTreeSet instead of HashSet, the same reason as discussed above
+        users: BTreeSet<String>,
+    },
+
+    /// For a badge that uses the "by allowlist" mint rule, remove the specified addresses from the
+    /// allowlist. Only callable by the manager. Addresses that are not on the allowlist are
+    /// ignored.
+    RemoveAllowlist {
+        id: u64,
+        /// NOTE: Use BTreeSet instead of HashSet, the same reason as discussed above
+        users: BTreeSet<String>,
+    },
+
+    /// Once a badge has expired or sold out, the allowlist is no longer needed. Invoke this method
+    /// to purge the allowlisted addresses from storage in order to reduce the chain's state size.
+    PurgeAllowlist {
+        id: u64,
+        limit: Option<u32>,
+    },
+
     /// Once a badge has expired or sold out, the list of users who have claimed it is no longer
     /// needed. Invoke this method to purge these user addresses in order to reduce the chain's
     /// state size.
@@ -84,19 +229,109 @@ pub enum ExecuteMsg {
     },
 
     /// For a badge with the "by minter" mint rule, mint new badges to a set of owners.
-    /// Can only be invoked by the designated minter.
+    /// Can only be invoked by the designated minter. If the badge requires consent, pending offers
+    /// are made to the owners instead.
     MintByMinter {
         id: u64,
         /// NOTE: User BTreeSet instead of HashSet, the same reason as discussed above
         owners: BTreeSet<String>,
     },
 
+    /// For a badge that requires consent, accept the pending offer made to the sender, which mints
+    /// the badge to them. The badge must still be available.
+    AcceptOffer {
+        id: u64,
+    },
+
+    /// For a badge that requires consent, reject the pending offer made to the sender.
+    RejectOffer {
+        id: u64,
+    },
+
+    /// Once a badge has expired or sold out, the pending offers can no longer be accepted. Invoke
+    /// this method to purge them from storage in order to reduce the chain's state size.
+    PurgeOffers {
+        id: u64,
+        limit: Option<u32>,
+    },
+
+    /// Once a badge has expired or sold out, the record of which collection tokens have been used
+    /// to claim it is no longer needed. Invoke this method to purge it from storage in order to
+    /// reduce the chain's state size.
+    PurgeCollectionTokens {
+        id: u64,
+        limit: Option<u32>,
+    },
+
+    /// Once a badge has expired or sold out, the nonces of the vouchers that have been redeemed for
+    /// it are no longer needed. Invoke this method to purge them from storage in order to reduce
+    /// the chain's state size.
+    PurgeNonces {
+        id: u64,
+        limit: Option<u32>,
+    },
+
+    /// Revoke an instance of a badge, for example if it was obtained by cheating. Only callable by
+    /// the badge's manager. The token is not burned, but is marked as revoked in its metadata and
+    /// can no longer be transferred. The badge's supply is unchanged, as serials are never reused.
+    RevokeInstance {
+        id: u64,
+        serial: u64,
+        reason: Option<String>,
+    },
+
+    /// Block the specified badge from ever being minted to the sender, under any mint rule.
+    BlockBadge {
+        id: u64,
+    },
+
+    /// Unblock a badge that the sender has previously blocked.
+    UnblockBadge {
+        id: u64,
+    },
+
+    /// Block all badges managed by the specified address, or minted by it under the "by minter"
+    /// rule, from ever being minted to the sender, under any mint rule.
+    ///
+    /// NOTE: The block applies to the address, so a manager can evade it by handing their badges
+    /// over to a new address using `transfer_manager`. To block a specific badge for good, use
+    /// `block_badge` instead.
+    BlockManager {
+        manager: String,
+    },
+
+    /// Unblock a manager that the sender has previously blocked.
+    UnblockManager {
+        manager: String,
+    },
+
+    /// For a badge with the "by minter" mint rule, redeem a voucher signed off-chain by the minter
+    /// to mint a badge to the specified owner, so that the minter doesn't need to submit the
+    /// minting transaction itself. Can be invoked by anyone holding the voucher, unless the badge
+    /// requires consent, in which case only the owner can redeem it.
+    ///
+    /// The voucher is the minter's secp256k1 signature of the SHA-256 hash of the message
+    /// "badges voucher\nchain: {chain_id}\nhub: {hub}\nbadge: {id}\nowner: {owner}\nnonce:
+    /// {nonce}\ndeadline: {deadline}". Each nonce can only be used once per badge.
+    MintByVoucher {
+        id: u64,
+        owner: String,
+        nonce: u64,
+        /// The timestamp after which the voucher can no longer be redeemed
+        deadline: u64,
+        /// The minter's hex-encoded compressed pubkey, from which the minter's address is derived
+        pubkey: String,
+        signature: String,
+    },
+
     /// For a badge with the "by key" mint rule, mint a badge to the specified owner.
     /// The caller must submit a signature to prove they have the minting key.
     MintByKey {
         id: u64,
         owner: String,
         signature: String,
+        /// For the v2 message format, the deadline included in the signed message, if any
+        deadline: Option<u64>,
     },
 
     /// For a badge with the "by keys" mint rule, mint a badge to the specified owner.
@@ -106,6 +341,105 @@ pub enum ExecuteMsg {
         owner: String,
         pubkey: String,
         signature: String,
+        /// For the v2 message format, the deadline included in the signed message, if any
+        deadline: Option<u64>,
+    },
+
+    /// For a badge with the "by key" mint rule, mint badges to multiple owners in one message, each
+    /// with their own signature, e.g. when relaying claims for an event.
+    ///
+    /// By default, the claims are minted atomically, i.e. the whole message fails if any of them
+    /// fails. If `skip_failures` is true, failed claims are skipped instead, and reported in the
+    /// response data, which is a BatchMintResponse.
+    BatchMintByKey {
+        id: u64,
+        claims: Vec<SignatureClaim>,
+        #[serde(default)]
+        skip_failures: bool,
+    },
+
+    /// For a badge with the "by keys" mint rule, mint badges to multiple owners in one message,
+    /// each with their own whitelisted key and signature, e.g. when relaying claims for an event.
+    ///
+    /// By default, the claims are minted atomically, i.e. the whole message fails if any of them
+    /// fails. If `skip_failures` is true, failed claims are skipped instead, and reported in the
+    /// response data, which is a BatchMintResponse.
+    BatchMintByKeys {
+        id: u64,
+        claims: Vec<KeyClaim>,
+        #[serde(default)]
+        skip_failures: bool,
+    },
+
+    /// For a badge with the "by allowlist" mint rule, claim a badge for the sender, who must be on
+    /// the allowlist.
+    MintByAllowlist {
+        id: u64,
+    },
+
+    /// For a badge with the "by merkle root" mint rule, mint a badge to the specified owner.
+    /// The caller must submit a Merkle proof that the owner's address is in the tree, consisting of
+    /// the hex-encoded sibling hashes from the leaf up to the root.
+    MintByMerkleProof {
+        id: u64,
+        owner: String,
+        proof: Vec<String>,
+        /// The amount committed to in the owner's leaf, if any. The owner can claim the badge as
+        /// many times as the amount, or once if the leaf has no amount.
+        #[serde(default)]
+        amount: Option<u64>,
+    },
+
+    /// For a badge with the "by payment" mint rule, mint a badge to the specified owner.
+    /// The sender must send at least the price along with the message; any excess is refunded.
+    MintByPayment {
+        id: u64,
+        owner: String,
+    },
+
+    /// For a badge with the "by prerequisites" mint rule, mint a badge to the specified owner.
+    /// The owner must hold the prerequisite badges at the time of minting.
+    MintByPrerequisites {
+        id: u64,
+        owner: String,
+    },
+
+    /// For a badge with the "by oracle" mint rule, mint a badge to the specified owner.
+    /// The oracle contract must deem the owner eligible. The optional proof is forwarded to the
+    /// oracle as is.
+    MintByOracle {
+        id: u64,
+        owner: String,
+        proof: Option<Binary>,
+    },
+
+    /// For a badge with the "by collection" mint rule, mint a badge to the specified owner.
+    /// The owner must own the specified token in the collection, and the token must not have
+    /// already been used to claim this badge.
+    MintByCollection {
+        id: u64,
+        owner: String,
+        token_id: String,
+    },
+
+    /// For a badge with the "by balance" mint rule, mint a badge to the specified owner.
+    /// The owner must hold no less than the required amount of the token.
+    MintByBalance {
+        id: u64,
+        owner: String,
+    },
+
+    /// Mint a badge to the specified owner, under any mint rule, including composite ones.
+    /// Provide the proofs needed by the rule or its sub-rules, e.g. a signature for "by key", or a
+    /// token id for "by collection". Funds needed by a "by payment" rule are sent along with the
+    /// message.
+    Mint {
+        id: u64,
+        owner: String,
+        #[serde(default)]
+        proofs: Vec<MintProof>,
+        /// For the v2 message format, the deadline included in the signed message, if any
+        deadline: Option<u64>,
     },
 
     /// During deployment, once the NFT contract has been deployed, the developer informs Hub of the
@@ -121,6 +455,25 @@ pub enum ExecuteMsg {
     },
 }
 
+/// A claim of a badge under the "by key" rule, submitted as part of a batch
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SignatureClaim {
+    pub owner: String,
+    pub signature: String,
+    /// For the v2 message format, the deadline included in the signed message, if any
+    pub deadline: Option<u64>,
+}
+
+/// A claim of a badge under the "by keys" rule, submitted as part of a batch
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct KeyClaim {
+    pub owner: String,
+    pub pubkey: String,
+    pub signature: String,
+    /// For the v2 message format, the deadline included in the signed message, if any
+    pub deadline: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
@@ -138,20 +491,107 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
-    /// Whether a pubkey can be used to mint a badge. Returns KeyResponse
+    /// The account that has been proposed as the badge's new manager but has not accepted yet.
+    /// Returns PendingManagerResponse
+    PendingManager {
+        id: u64,
+    },
+
+    /// Whether a pubkey can be used to mint a badge, and if so, its remaining uses, expiry, and
+    /// tier. Returns KeyResponse
     Key {
         id: u64,
         pubkey: String,
     },
 
-    /// List all pubkeys that can be used to mint a badge. Returns KeysResponse
+    /// List all pubkeys that can be used to mint a badge, along with their remaining uses, expiry,
+    /// and tier. Returns KeysResponse
     Keys {
         id: u64,
         start_after: Option<String>,
         limit: Option<u32>,
     },
 
-    /// Whether a user has claimed the specified badge. Returns OwnerResponse
+    /// Whether a user is on the allowlist of a badge under the "by allowlist" rule.
+    /// Returns AllowlistedResponse
+    Allowlisted {
+        id: u64,
+        user: String,
+    },
+
+    /// List all users on the allowlist of a badge. Returns AllowlistResponse
+    Allowlist {
+        id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Whether a token of the collection has been used to claim a badge under the "by collection"
+    /// rule. Returns CollectionTokenResponse
+    CollectionToken {
+        id: u64,
+        token_id: String,
+    },
+
+    /// Whether a voucher nonce has been used to mint a badge under the "by minter" rule.
+    /// Returns VoucherResponse
+    Voucher {
+        id: u64,
+        nonce: u64,
+    },
+
+    /// The message to be signed to claim a badge for a user under the "by key" or "by keys" rule,
+    /// in the format used by the badge. The deadline is ignored for the legacy format.
+    /// Returns ClaimMessageResponse
+    ClaimMessage {
+        id: u64,
+        user: String,
+        deadline: Option<u64>,
+    },
+
+    /// Info about an instance of a badge, i.e. the tier of the key it was minted with, if any, and
+    /// whether it has been revoked. Returns InstanceResponse
+    Instance {
+        id: u64,
+        serial: u64,
+    },
+
+    /// Whether a user has a pending offer of a badge that requires consent. Returns OfferResponse
+    Offer {
+        id: u64,
+        user: String,
+    },
+
+    /// List all users with pending offers of a badge. Returns OffersResponse
+    Offers {
+        id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Whether a user has blocked a badge from being minted to them, either by its id or by its
+    /// manager. Returns BlockedResponse
+    Blocked {
+        id: u64,
+        user: String,
+    },
+
+    /// List the ids of badges that a user has blocked. Returns BlockedBadgesResponse
+    BlockedBadges {
+        user: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// List the managers that a user has blocked. Returns BlockedManagersResponse
+    BlockedManagers {
+        user: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Whether a user has claimed the specified badge, and how many instances they have claimed.
+    /// Returns OwnerResponse
     Owner {
         id: u64,
         user: String,
@@ -176,13 +616,19 @@ pub struct ConfigResponse {
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct BadgeResponse {
     pub id: u64,
-    pub manager: String,
+    pub manager: Option<String>,
     pub metadata: Metadata,
     pub transferrable: bool,
     pub rule: MintRule,
+    pub start: Option<u64>,
     pub expiry: Option<u64>,
+    pub phases: Vec<Phase>,
     pub max_supply: Option<u64>,
     pub current_supply: u64,
+    pub paused: bool,
+    pub message_version: MessageVersion,
+    pub max_per_user: Option<u64>,
+    pub require_consent: bool,
 }
 
 impl From<(u64, Badge)> for BadgeResponse {
@@ -190,13 +636,19 @@ impl From<(u64, Badge)> for BadgeResponse {
         let (id, badge) = item;
         BadgeResponse {
             id,
-            manager: badge.manager.into(),
+            manager: badge.manager.map(String::from),
             metadata: badge.metadata,
             transferrable: badge.transferrable,
             rule: badge.rule,
+            start: badge.start,
             expiry: badge.expiry,
+            phases: badge.phases,
             max_supply: badge.max_supply,
             current_supply: badge.current_supply,
+            paused: badge.paused,
+            message_version: badge.message_version,
+            max_per_user: badge.max_per_user,
+            require_consent: badge.require_consent,
         }
     }
 }
@@ -206,24 +658,117 @@ pub struct BadgesResponse {
     pub badges: Vec<BadgeResponse>
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingManagerResponse {
+    pub id: u64,
+    pub pending_manager: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AllowlistedResponse {
+    pub user: String,
+    pub allowlisted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AllowlistResponse {
+    pub users: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CollectionTokenResponse {
+    pub token_id: String,
+    pub used: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VoucherResponse {
+    pub nonce: u64,
+    pub used: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClaimMessageResponse {
+    pub version: MessageVersion,
+    pub message: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct KeyResponse {
     pub key: String,
     pub whitelisted: bool,
+    /// The key's type, remaining uses, expiry, and tier if it is whitelisted, or None otherwise
+    pub info: Option<KeyInfo>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct KeysResponse {
-    pub keys: Vec<String>,
+    pub keys: Vec<KeyResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InstanceResponse {
+    pub id: u64,
+    pub serial: u64,
+    pub tier: Option<String>,
+    /// Whether the instance has been revoked by the badge's manager
+    pub revoked: bool,
+    /// The reason given by the manager for revoking the instance, if any
+    pub revocation_reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OfferResponse {
+    pub user: String,
+    pub pending: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OffersResponse {
+    pub users: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BlockedResponse {
+    pub user: String,
+    pub blocked: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BlockedBadgesResponse {
+    pub ids: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BlockedManagersResponse {
+    pub managers: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct OwnerResponse {
     pub user: String,
     pub claimed: bool,
+    /// The number of instances the user has claimed, not including those minted by the minter
+    pub count: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct OwnersResponse {
     pub owners: Vec<String>,
 }
+
+/// The data of the response to a `batch_mint_by_key` or `batch_mint_by_keys` message
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BatchMintResponse {
+    /// Owners to whom the badge has been minted, in the order of the claims
+    pub minted: Vec<String>,
+    /// Claims that have been skipped, in the order of the claims
+    pub failed: Vec<FailedClaim>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FailedClaim {
+    pub owner: String,
+    /// The error message explaining why the claim failed
+    pub error: String,
+}
@@ -5,7 +5,8 @@ mod fee;
 pub mod hub;
 mod mint_rule;
 pub mod nft;
+pub mod oracle;
 
-pub use badge::Badge;
+pub use badge::{Badge, MessageVersion, Phase};
 pub use fee::FeeRate;
-pub use mint_rule::MintRule;
+pub use mint_rule::{Denom, KeyInfo, KeyType, MintProof, MintRule};
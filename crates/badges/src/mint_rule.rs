@@ -1,8 +1,84 @@
-use std::fmt;
+use std::{collections::BTreeSet, fmt};
 
+use cosmwasm_std::{Binary, Coin, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// The type of a public key used to sign claim messages
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyType {
+    /// A secp256k1 public key, either compressed or uncompressed. The signature is produced by
+    /// signing the SHA-256 hash of the claim message.
+    #[default]
+    Secp256k1,
+
+    /// An Ed25519 public key. The signature is produced by signing the claim message itself.
+    Ed25519,
+
+    /// A compressed secp256k1 public key of a Cosmos wallet. The signature is produced by the
+    /// wallet's `signArbitrary` method (e.g. in Keplr or Leap), which signs an ADR-036 sign doc
+    /// wrapping the claim message.
+    Adr036,
+
+    /// An Ethereum address, hex-encoded, in place of a public key. The `0x` prefix is optional, and
+    /// so is the case; addresses are stored and compared without the prefix, in lowercase. The
+    /// signature is produced by an Ethereum wallet's `personal_sign` method (EIP-191), and the
+    /// signer's address is recovered from it.
+    Ethereum,
+}
+
+/// A key whitelisted to mint a badge under the "by keys" rule
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct KeyInfo {
+    /// The type of the key
+    pub key_type: KeyType,
+
+    /// The number of times the key can still be used to mint. The key is removed once this reaches
+    /// zero.
+    pub uses: u64,
+
+    /// The timestamp only before which the key can be used. None means the key has no expiry of its
+    /// own, although it still can't be used once the badge becomes unavailable.
+    pub expiry: Option<u64>,
+
+    /// An optional label or tier, which is stamped on each badge instance minted using the key as
+    /// the `tier` trait. Must not be longer than 32 bytes.
+    pub tier: Option<String>,
+}
+
+impl KeyInfo {
+    /// A key of the specified type that can be used once, with no expiry or tier
+    pub fn new(key_type: KeyType) -> Self {
+        KeyInfo {
+            key_type,
+            uses: 1,
+            expiry: None,
+            tier: None,
+        }
+    }
+}
+
+/// A fungible token, which is either a native coin or a cw20 token
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Denom {
+    /// A native coin, specified by its denom
+    Native(String),
+
+    /// A cw20 token, specified by its contract address
+    Cw20(String),
+}
+
+impl fmt::Display for Denom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Denom::Native(denom) => write!(f, "native:{}", denom),
+            Denom::Cw20(contract) => write!(f, "cw20:{}", contract),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum MintRule {
@@ -15,27 +91,180 @@ pub enum MintRule {
     /// Badges can be minted upon the the signature signed by a designated private key. Provide the
     /// associated public key in hex encoding.
     ///
+    /// The key is secp256k1 unless otherwise specified.
+    ///
     /// This key can be reused as many time as possible for minting, as long as the badge's deadline
     /// and max supply have not been reached.
-    ByKey(String),
+    ///
+    /// The manager can replace the key using the `rotate_key` execute method.
+    ByKey {
+        key: String,
+        #[serde(default)]
+        key_type: KeyType,
+    },
 
-    /// Similar to the `ByKey` rule, but there are multiple pubkeys, each can only be used once.
+    /// Similar to the `ByKey` rule, but there are multiple pubkeys, each can only be used a limited
+    /// number of times (once by default), and optionally only before its own expiry. A key can also
+    /// carry a tier, which is stamped on the badge instances minted using it.
     ///
     /// To add a pubkey, use the `add_key` execute method. Keys can only be added before the minting
-    /// deadline and max supply haven't been reached.
+    /// deadline and max supply haven't been reached. Each key is stored along with its type, so
+    /// secp256k1 and Ed25519 keys can be whitelisted for the same badge.
+    ///
+    /// With keys of the Ethereum type, this rule works as an allowlist of Ethereum addresses: the
+    /// holder of each address signs the claim message, which specifies their Stargaze address,
+    /// using their Ethereum wallet.
     ///
     /// Once either the minting deadline or the max supply is reached, anyone can invoke the
     /// `clear_keys` method to remove unused keys from the contract storage, thereby reducing the
     /// size of the chain's state.
     ByKeys,
+
+    /// Badges can be claimed by users whose addresses are on an allowlist stored in the contract.
+    /// Each user claims the badge themselves, without needing a key or a signature.
+    ///
+    /// To add addresses, use the `add_allowlist` execute method. As with keys, addresses can only
+    /// be added before the minting deadline and max supply haven't been reached, and anyone can
+    /// invoke the `purge_allowlist` method to remove them once either is reached.
+    ///
+    /// This is suitable for small events. For a large allowlist, consider the "by merkle root"
+    /// rule instead.
+    ByAllowlist,
+
+    /// Badges can be minted by users whose addresses are included in a Merkle tree. Provide the
+    /// root of the tree in hex encoding.
+    ///
+    /// Each leaf of the tree is the SHA-256 hash of either a user address, or a user address and
+    /// an amount joined by a colon, i.e. `{address}:{amount}`. A user can claim the badge as many
+    /// times as the amount in their leaf, or once if the leaf has no amount. Each parent node is
+    /// the SHA-256 hash of its two children concatenated, with the smaller one (in bytes) first.
+    /// This way, a large allowlist can be stored for the cost of a single hash, instead of
+    /// whitelisting each account using the "by keys" rule.
+    ByMerkleRoot {
+        root: String,
+    },
+
+    /// Badges can be minted by anyone who pays the specified price. The payment is forwarded to the
+    /// recipient, after deducting the protocol fee if the price is in ustars. Overpayments are
+    /// refunded to the sender.
+    ByPayment {
+        price: Coin,
+        recipient: String,
+    },
+
+    /// Badges can be minted by users who hold certain other badges.
+    ///
+    /// The user must hold at least one instance of every badge in `all_of`, and, if `any_of` is
+    /// non-empty, at least one instance of any of the badges in `any_of`.
+    ///
+    /// Ownership is checked against the NFT contract at the time of minting, so a user who has
+    /// transferred away a prerequisite badge no longer qualifies. Revoked instances don't count.
+    ByPrerequisites {
+        all_of: BTreeSet<u64>,
+        any_of: BTreeSet<u64>,
+    },
+
+    /// Badges can be minted by users deemed eligible by an external contract.
+    ///
+    /// Upon minting, the Hub queries the contract with `badges::oracle::QueryMsg::IsEligible`.
+    /// Unlike with the "by minter" rule, the oracle only needs to decide eligibility; the Hub
+    /// itself handles the minting, deduplication, and supply accounting.
+    ByOracle {
+        contract: String,
+    },
+
+    /// Badges can be minted by holders of an NFT from the specified cw721 or sg721 collection.
+    ///
+    /// The claimer names a token id, whose ownership is verified by querying the collection. Each
+    /// token can only be used once per badge, so that one NFT can't be passed around between
+    /// wallets to claim multiple badges.
+    ByCollection {
+        collection: String,
+    },
+
+    /// Badges can be minted by users who hold no less than the specified amount of a native coin
+    /// or a cw20 token.
+    ///
+    /// The balance is checked at the time of minting, by a bank query for native coins, or a
+    /// `balance` query for cw20 tokens.
+    ByBalance {
+        denom: Denom,
+        amount: Uint128,
+    },
+
+    /// Badges can be minted by users who satisfy all of the sub-rules.
+    ///
+    /// Badges using a composite rule can only be minted with the generic `mint` message, which
+    /// takes the proofs needed by the sub-rules. A composite rule may contain at most one "by
+    /// payment" sub-rule.
+    All(Vec<MintRule>),
+
+    /// Badges can be minted by users who satisfy any of the sub-rules. The sub-rules are checked in
+    /// order, and only the first one satisfied takes effect, e.g. consumes a key or a payment.
+    Any(Vec<MintRule>),
+}
+
+/// A proof submitted with the generic `mint` message. Each sub-rule that requires a proof uses the
+/// first one of the matching kind.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MintProof {
+    /// For the "by key" rule: the hex-encoded signature
+    Signature(String),
+
+    /// For the "by keys" rule: the hex-encoded whitelisted pubkey, and the signature
+    KeySignature {
+        pubkey: String,
+        signature: String,
+    },
+
+    /// For the "by merkle root" rule: the hex-encoded sibling hashes from the leaf up to the root,
+    /// and the amount committed to in the leaf, if any
+    MerkleProof {
+        proof: Vec<String>,
+        #[serde(default)]
+        amount: Option<u64>,
+    },
+
+    /// For the "by oracle" rule: the data to be forwarded to the oracle
+    OracleProof(Binary),
+
+    /// For the "by collection" rule: the id of the token owned in the collection
+    CollectionToken(String),
 }
 
 impl fmt::Display for MintRule {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let s = match self {
             MintRule::ByMinter(minter) => format!("by_minter:{}", minter),
-            MintRule::ByKey(pubkey) => format!("by_key:{}", pubkey),
+            MintRule::ByKey {
+                key,
+                ..
+            } => format!("by_key:{}", key),
             MintRule::ByKeys => "by_keys".to_string(),
+            MintRule::ByAllowlist => "by_allowlist".to_string(),
+            MintRule::ByMerkleRoot {
+                root,
+            } => format!("by_merkle_root:{}", root),
+            MintRule::ByPayment {
+                price,
+                recipient,
+            } => format!("by_payment:{}:{}", price, recipient),
+            MintRule::ByPrerequisites {
+                ..
+            } => "by_prerequisites".to_string(),
+            MintRule::ByOracle {
+                contract,
+            } => format!("by_oracle:{}", contract),
+            MintRule::ByCollection {
+                collection,
+            } => format!("by_collection:{}", collection),
+            MintRule::ByBalance {
+                denom,
+                amount,
+            } => format!("by_balance:{}:{}", denom, amount),
+            MintRule::All(rules) => format!("all({})", join_rules(rules)),
+            MintRule::Any(rules) => format!("any({})", join_rules(rules)),
         };
         write!(f, "{}", s)
     }
@@ -47,6 +276,53 @@ impl MintRule {
     }
 
     pub fn by_key(key: impl Into<String>) -> Self {
-        MintRule::ByKey(key.into())
+        MintRule::ByKey {
+            key: key.into(),
+            key_type: KeyType::Secp256k1,
+        }
+    }
+
+    /// Whether the rule is the "by keys" rule, or a composite rule containing it, in which case the
+    /// manager can whitelist keys for the badge.
+    pub fn uses_keys(&self) -> bool {
+        match self {
+            MintRule::ByKeys => true,
+            MintRule::All(rules) | MintRule::Any(rules) => rules.iter().any(MintRule::uses_keys),
+            _ => false,
+        }
     }
+
+    /// Whether the rule is the "by allowlist" rule, or a composite rule containing it, in which
+    /// case the manager can add addresses to the allowlist of the badge.
+    pub fn uses_allowlist(&self) -> bool {
+        match self {
+            MintRule::ByAllowlist => true,
+            MintRule::All(rules) | MintRule::Any(rules) => {
+                rules.iter().any(MintRule::uses_allowlist)
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the rule is the "by key" rule, or a composite rule containing it. Its signatures are
+    /// not consumed upon minting, so the same signature could be replayed to claim the badge again
+    /// if the user is allowed to claim more than once.
+    ///
+    /// Merkle proofs are reusable too, but the number of claims they allow is committed to in the
+    /// leaf, and enforced against the user's claim count.
+    pub fn has_reusable_proofs(&self) -> bool {
+        match self {
+            MintRule::ByKey {
+                ..
+            } => true,
+            MintRule::All(rules) | MintRule::Any(rules) => {
+                rules.iter().any(MintRule::has_reusable_proofs)
+            },
+            _ => false,
+        }
+    }
+}
+
+fn join_rules(rules: &[MintRule]) -> String {
+    rules.iter().map(|rule| rule.to_string()).collect::<Vec<_>>().join(",")
 }
@@ -0,0 +1,23 @@
+use cosmwasm_std::Binary;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The query interface that an eligibility oracle must implement in order to be used with the
+/// "by oracle" mint rule. The oracle may implement other queries as well.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Whether a user is eligible to claim the specified badge. Returns IsEligibleResponse
+    IsEligible {
+        badge_id: u64,
+        user: String,
+        /// Optional data submitted by the claimer to help the oracle determine eligibility, e.g.
+        /// a signature or a proof. The Hub forwards it to the oracle as is.
+        proof: Option<Binary>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct IsEligibleResponse {
+    pub eligible: bool,
+}
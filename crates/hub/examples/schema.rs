@@ -4,8 +4,11 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use badges::hub::{
-    BadgeResponse, BadgesResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, KeyResponse,
-    KeysResponse, OwnerResponse, OwnersResponse, QueryMsg,
+    AllowlistResponse, AllowlistedResponse, BadgeResponse, BadgesResponse, BatchMintResponse,
+    BlockedBadgesResponse, BlockedManagersResponse, BlockedResponse, ClaimMessageResponse,
+    CollectionTokenResponse, ConfigResponse, ExecuteMsg, InstanceResponse, InstantiateMsg,
+    KeyResponse, KeysResponse, MigrateMsg, OfferResponse, OffersResponse, OwnerResponse,
+    OwnersResponse, PendingManagerResponse, QueryMsg, SudoMsg, VoucherResponse,
 };
 
 fn main() {
@@ -17,12 +20,27 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(SudoMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
 
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(BadgeResponse), &out_dir);
     export_schema(&schema_for!(BadgesResponse), &out_dir);
+    export_schema(&schema_for!(BatchMintResponse), &out_dir);
+    export_schema(&schema_for!(PendingManagerResponse), &out_dir);
     export_schema(&schema_for!(KeyResponse), &out_dir);
     export_schema(&schema_for!(KeysResponse), &out_dir);
+    export_schema(&schema_for!(AllowlistedResponse), &out_dir);
+    export_schema(&schema_for!(AllowlistResponse), &out_dir);
+    export_schema(&schema_for!(OfferResponse), &out_dir);
+    export_schema(&schema_for!(OffersResponse), &out_dir);
+    export_schema(&schema_for!(BlockedResponse), &out_dir);
+    export_schema(&schema_for!(BlockedBadgesResponse), &out_dir);
+    export_schema(&schema_for!(BlockedManagersResponse), &out_dir);
+    export_schema(&schema_for!(CollectionTokenResponse), &out_dir);
+    export_schema(&schema_for!(VoucherResponse), &out_dir);
+    export_schema(&schema_for!(ClaimMessageResponse), &out_dir);
+    export_schema(&schema_for!(InstanceResponse), &out_dir);
     export_schema(&schema_for!(OwnerResponse), &out_dir);
     export_schema(&schema_for!(OwnersResponse), &out_dir);
 }
@@ -1,11 +1,11 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, StdResult,
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, StdResult,
 };
 use sg_std::Response;
 
 use badges::{
-    hub::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg},
-    Badge,
+    hub::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg},
+    Badge, KeyInfo,
 };
 
 use crate::{error::ContractError, execute, query, upgrades};
@@ -19,13 +19,13 @@ pub fn instantiate(
     _env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     execute::init(deps, info.sender, msg.fee_rate)
 }
 
 #[entry_point]
-pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> StdResult<Response> {
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     match msg {
         SudoMsg::SetFeeRate {
             fee_rate,
@@ -46,17 +46,28 @@ pub fn execute(
             metadata,
             transferrable,
             rule,
+            start,
             expiry,
+            phases,
             max_supply,
+            message_version,
+            max_per_user,
+            require_consent,
         } => {
             let badge = Badge {
-                manager: deps.api.addr_validate(&manager)?,
+                manager: Some(deps.api.addr_validate(&manager)?),
                 metadata,
                 transferrable,
                 rule,
+                start,
                 expiry,
+                phases,
                 max_supply,
                 current_supply: 0,
+                paused: false,
+                message_version,
+                max_per_user,
+                require_consent,
             };
             execute::create_badge(deps, env, info, badge)
         },
@@ -64,14 +75,71 @@ pub fn execute(
             id,
             metadata,
         } => execute::edit_badge(deps, info, id, metadata),
+        ExecuteMsg::EditBadgeConfig {
+            id,
+            expiry,
+            max_supply,
+            transferrable,
+        } => execute::edit_badge_config(deps, env, info, id, expiry, max_supply, transferrable),
+        ExecuteMsg::SetPaused {
+            id,
+            paused,
+        } => execute::set_paused(deps, info, id, paused),
+        ExecuteMsg::TransferManager {
+            id,
+            new_manager,
+        } => execute::transfer_manager(deps, info, id, new_manager),
+        ExecuteMsg::AcceptManager {
+            id,
+        } => execute::accept_manager(deps, info, id),
+        ExecuteMsg::RenounceManager {
+            id,
+        } => execute::renounce_manager(deps, info, id),
+        ExecuteMsg::RotateKey {
+            id,
+            key,
+            key_type,
+        } => execute::rotate_key(deps, env, info, id, key, key_type),
         ExecuteMsg::AddKeys {
             id,
             keys,
-        } => execute::add_keys(deps, env, info, id, keys),
+            key_type,
+            uses,
+            expiry,
+            tier,
+        } => {
+            let key_info = KeyInfo {
+                key_type,
+                uses: uses.unwrap_or(1),
+                expiry,
+                tier,
+            };
+            execute::add_keys(deps, env, info, id, keys, key_info)
+        },
+        ExecuteMsg::RevokeKeys {
+            id,
+            keys,
+        } => execute::revoke_keys(deps, info, id, keys),
+        ExecuteMsg::RevokeAllKeys {
+            id,
+            limit,
+        } => execute::revoke_all_keys(deps, info, id, limit),
         ExecuteMsg::PurgeKeys {
             id,
             limit,
         } => execute::purge_keys(deps, env, id, limit),
+        ExecuteMsg::AddAllowlist {
+            id,
+            users,
+        } => execute::add_allowlist(deps, env, info, id, users),
+        ExecuteMsg::RemoveAllowlist {
+            id,
+            users,
+        } => execute::remove_allowlist(deps, info, id, users),
+        ExecuteMsg::PurgeAllowlist {
+            id,
+            limit,
+        } => execute::purge_allowlist(deps, env, id, limit),
         ExecuteMsg::PurgeOwners {
             id,
             limit,
@@ -80,17 +148,119 @@ pub fn execute(
             id,
             owners,
         } => execute::mint_by_minter(deps, env, id, owners, info.sender),
+        ExecuteMsg::AcceptOffer {
+            id,
+        } => execute::accept_offer(deps, env, info, id),
+        ExecuteMsg::RejectOffer {
+            id,
+        } => execute::reject_offer(deps, info, id),
+        ExecuteMsg::PurgeOffers {
+            id,
+            limit,
+        } => execute::purge_offers(deps, env, id, limit),
+        ExecuteMsg::PurgeCollectionTokens {
+            id,
+            limit,
+        } => execute::purge_collection_tokens(deps, env, id, limit),
+        ExecuteMsg::PurgeNonces {
+            id,
+            limit,
+        } => execute::purge_nonces(deps, env, id, limit),
+        ExecuteMsg::RevokeInstance {
+            id,
+            serial,
+            reason,
+        } => execute::revoke_instance(deps, info, id, serial, reason),
+        ExecuteMsg::BlockBadge {
+            id,
+        } => execute::block_badge(deps, info, id),
+        ExecuteMsg::UnblockBadge {
+            id,
+        } => execute::unblock_badge(deps, info, id),
+        ExecuteMsg::BlockManager {
+            manager,
+        } => execute::block_manager(deps, info, manager),
+        ExecuteMsg::UnblockManager {
+            manager,
+        } => execute::unblock_manager(deps, info, manager),
+        ExecuteMsg::MintByVoucher {
+            id,
+            owner,
+            nonce,
+            deadline,
+            pubkey,
+            signature,
+        } => execute::mint_by_voucher(
+            deps,
+            env,
+            id,
+            owner,
+            nonce,
+            deadline,
+            pubkey,
+            signature,
+            info.sender,
+        ),
         ExecuteMsg::MintByKey {
             id,
             owner,
             signature,
-        } => execute::mint_by_key(deps, env, id, owner, signature),
+            deadline,
+        } => execute::mint_by_key(deps, env, id, owner, signature, deadline),
         ExecuteMsg::MintByKeys {
             id,
             owner,
             pubkey,
             signature,
-        } => execute::mint_by_keys(deps, env, id, owner, pubkey, signature),
+            deadline,
+        } => execute::mint_by_keys(deps, env, id, owner, pubkey, signature, deadline),
+        ExecuteMsg::BatchMintByKey {
+            id,
+            claims,
+            skip_failures,
+        } => execute::batch_mint_by_key(deps, env, id, claims, skip_failures),
+        ExecuteMsg::BatchMintByKeys {
+            id,
+            claims,
+            skip_failures,
+        } => execute::batch_mint_by_keys(deps, env, id, claims, skip_failures),
+        ExecuteMsg::MintByAllowlist {
+            id,
+        } => execute::mint_by_allowlist(deps, env, id, info.sender),
+        ExecuteMsg::MintByMerkleProof {
+            id,
+            owner,
+            proof,
+            amount,
+        } => execute::mint_by_merkle_proof(deps, env, id, owner, proof, amount),
+        ExecuteMsg::MintByPayment {
+            id,
+            owner,
+        } => execute::mint_by_payment(deps, env, info, id, owner),
+        ExecuteMsg::MintByPrerequisites {
+            id,
+            owner,
+        } => execute::mint_by_prerequisites(deps, env, id, owner),
+        ExecuteMsg::MintByOracle {
+            id,
+            owner,
+            proof,
+        } => execute::mint_by_oracle(deps, env, id, owner, proof),
+        ExecuteMsg::MintByCollection {
+            id,
+            owner,
+            token_id,
+        } => execute::mint_by_collection(deps, env, id, owner, token_id),
+        ExecuteMsg::MintByBalance {
+            id,
+            owner,
+        } => execute::mint_by_balance(deps, env, id, owner),
+        ExecuteMsg::Mint {
+            id,
+            owner,
+            proofs,
+            deadline,
+        } => execute::mint(deps, env, info, id, owner, proofs, deadline),
         ExecuteMsg::SetNft {
             nft,
         } => execute::set_nft(deps, info.sender, &nft),
@@ -98,7 +268,7 @@ pub fn execute(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query::config(deps)?),
         QueryMsg::Badge {
@@ -108,19 +278,71 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             start_after,
             limit,
         } => to_binary(&query::badges(deps, start_after, limit)?),
+        QueryMsg::PendingManager {
+            id,
+        } => to_binary(&query::pending_manager(deps, id)?),
         QueryMsg::Key {
             id,
             pubkey,
-        } => to_binary(&query::key(deps, id, pubkey)),
+        } => to_binary(&query::key(deps, id, pubkey)?),
         QueryMsg::Keys {
             id,
             start_after,
             limit,
         } => to_binary(&query::keys(deps, id, start_after, limit)?),
+        QueryMsg::Allowlisted {
+            id,
+            user,
+        } => to_binary(&query::allowlisted(deps, id, user)),
+        QueryMsg::Allowlist {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query::allowlist(deps, id, start_after, limit)?),
+        QueryMsg::CollectionToken {
+            id,
+            token_id,
+        } => to_binary(&query::collection_token(deps, id, token_id)),
+        QueryMsg::Voucher {
+            id,
+            nonce,
+        } => to_binary(&query::voucher(deps, id, nonce)),
+        QueryMsg::ClaimMessage {
+            id,
+            user,
+            deadline,
+        } => to_binary(&query::claim_message(deps, env, id, user, deadline)?),
+        QueryMsg::Instance {
+            id,
+            serial,
+        } => to_binary(&query::instance(deps, id, serial)?),
+        QueryMsg::Offer {
+            id,
+            user,
+        } => to_binary(&query::offer(deps, id, user)),
+        QueryMsg::Offers {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query::offers(deps, id, start_after, limit)?),
+        QueryMsg::Blocked {
+            id,
+            user,
+        } => to_binary(&query::blocked(deps, id, user)?),
+        QueryMsg::BlockedBadges {
+            user,
+            start_after,
+            limit,
+        } => to_binary(&query::blocked_badges(deps, user, start_after, limit)?),
+        QueryMsg::BlockedManagers {
+            user,
+            start_after,
+            limit,
+        } => to_binary(&query::blocked_managers(deps, user, start_after, limit)?),
         QueryMsg::Owner {
             id,
             user,
-        } => to_binary(&query::owner(deps, id, user)),
+        } => to_binary(&query::owner(deps, id, user)?),
         QueryMsg::Owners {
             id,
             start_after,
@@ -130,7 +352,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 }
 
 #[entry_point]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let cw2::ContractVersion {
         contract,
         version,
@@ -140,11 +362,15 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
         return Err(ContractError::incorrect_contract_name(CONTRACT_NAME, contract));
     }
 
-    // in the previous v1.1 update, we forgot to set the contract version to `1.1.0`
-    // so for now it's still `1.0.0`
-    if version != "1.0.0" {
-        return Err(ContractError::incorrect_contract_version("1.0.0", version));
+    // the version is only bumped once the migration is complete, so this also allows a partial
+    // migration to be continued
+    if version != "1.2.0" {
+        return Err(ContractError::incorrect_contract_version("1.2.0", version));
+    }
+
+    if msg.limit == Some(0) {
+        return Err(ContractError::InvalidMigrationLimit);
     }
 
-    upgrades::v1_2::migrate(deps).map_err(ContractError::from)
+    upgrades::v1_3::migrate(deps, msg.limit).map_err(ContractError::from)
 }
@@ -1,3 +1,4 @@
+use cosmwasm_std::Uint128;
 use thiserror::Error;
 
 use badges::MintRule;
@@ -16,18 +17,68 @@ pub enum ContractError {
     #[error(transparent)]
     Fee(#[from] sg1::FeeError),
 
+    #[error(transparent)]
+    Payment(#[from] cw_utils::PaymentError),
+
     #[error(transparent)]
     FromHex(#[from] hex::FromHexError),
 
+    #[error(transparent)]
+    Bech32(#[from] bech32::Error),
+
+    #[error(transparent)]
+    Overflow(#[from] cosmwasm_std::OverflowError),
+
     #[error("invalid reply id {0}; must be 1")]
     InvalidReplyId(u64),
 
-    #[error("not a valid secp256k1 public key")]
+    #[error("badges with by_key rules must be limited to one claim per user")]
+    InvalidMaxPerUser,
+
+    #[error("the number of claims per user must not be zero")]
+    ZeroMaxPerUser,
+
+    #[error("the mint fee rate must not be greater than one")]
+    InvalidMintFeeRate,
+
+    #[error("the number of uses of a key must not be zero")]
+    InvalidKeyUses,
+
+    #[error("the tier of a key must not be longer than {max} bytes")]
+    TierTooLong {
+        max: usize,
+    },
+
+    #[error("not a valid public key of the specified type")]
     InvalidPubkey,
 
     #[error("signature verification failed")]
     InvalidSignature,
 
+    #[error("the deadline of the signed claim message has passed")]
+    DeadlinePassed,
+
+    #[error("not a valid merkle root; must be a hex-encoded sha256 hash")]
+    InvalidMerkleRoot,
+
+    #[error("merkle proof verification failed")]
+    InvalidMerkleProof,
+
+    #[error("mint price must not be zero")]
+    InvalidPrice,
+
+    #[error("invalid prerequisites: must specify at least one badge, and each badge must exist")]
+    InvalidPrerequisites,
+
+    #[error("invalid balance requirement: amount must not be zero, and denom must be valid")]
+    InvalidBalanceRequirement,
+
+    #[error("invalid composite rule: must contain at least one sub-rule, at most one by_payment rule, and an all rule must not need two proofs of the same kind")]
+    InvalidCompositeRule,
+
+    #[error("none of the sub-rules is satisfied")]
+    NoRuleSatisfied,
+
     #[error("variable cannot be initialized twice")]
     DoubleInit,
 
@@ -37,18 +88,42 @@ pub enum ContractError {
     #[error("unauthorized: sender is not badge manager")]
     NotManager,
 
+    #[error("unauthorized: sender is not badge pending manager")]
+    NotPendingManager,
+
     #[error("unauthorized: sender is not badge minter")]
     NotMinter,
 
     #[error("expecting the badge to be unavailable but it is available")]
     Available,
 
+    #[error("badge minting has not started yet")]
+    NotStarted,
+
     #[error("badge minting deadline has been been exceeded")]
     Expired,
 
     #[error("badge max supply has been been exceeded")]
     SoldOut,
 
+    #[error("badge minting has been paused by the manager")]
+    Paused,
+
+    #[error("badge max supply for the current minting phase has been exceeded")]
+    PhaseSoldOut,
+
+    #[error("invalid minting schedule: phases must be sorted and not overlap, and must not end before they start")]
+    InvalidSchedule,
+
+    #[error("new minting deadline must be later than the current one")]
+    InvalidExpiry,
+
+    #[error("new max supply must be lower than the current one and no lower than the current supply")]
+    InvalidMaxSupply,
+
+    #[error("a non-transferrable badge cannot be made transferrable")]
+    CannotMakeTransferrable,
+
     #[error("key {key} already exists for badge {id}")]
     KeyExists {
         id: u64,
@@ -60,13 +135,116 @@ pub enum ContractError {
         id: u64,
     },
 
+    #[error("the provided key has expired for badge {id}")]
+    KeyExpired {
+        id: u64,
+    },
+
+    #[error("user {user} is already on the allowlist of badge {id}")]
+    AlreadyAllowlisted {
+        id: u64,
+        user: String,
+    },
+
+    #[error("user {user} is not on the allowlist of badge {id}")]
+    NotAllowlisted {
+        id: u64,
+        user: String,
+    },
+
+    #[error("the by_allowlist rule requires the owner to claim the badge themselves")]
+    NotSelfClaim,
+
+    #[error("badge requires the recipient's consent")]
+    ConsentRequired,
+
+    #[error("badge {id} has no instance with serial {serial}")]
+    InstanceNotFound {
+        id: u64,
+        serial: u64,
+    },
+
+    #[error("instance {serial} of badge {id} has already been revoked")]
+    AlreadyRevoked {
+        id: u64,
+        serial: u64,
+    },
+
+    #[error("user {user} has blocked badge {id} or its manager")]
+    Blocked {
+        id: u64,
+        user: String,
+    },
+
+    #[error("user {user} has no pending offer of badge {id}")]
+    NoOffer {
+        id: u64,
+        user: String,
+    },
+
     #[error("user {user} has already claimed badge {id}")]
     AlreadyClaimed {
         id: u64,
         user: String,
     },
 
-    #[error("unknown mint rule {found}, expecting by_minter|key|keys")]
+    #[error("insufficient payment: expected {expected}, received {received}")]
+    InsufficientPayment {
+        expected: String,
+        received: String,
+    },
+
+    #[error("user {user} does not hold prerequisite badge {id}")]
+    MissingPrerequisite {
+        id: u64,
+        user: String,
+    },
+
+    #[error("user {user} does not hold any of the prerequisite badges")]
+    NoPrerequisite {
+        user: String,
+    },
+
+    #[error("user {user} is not eligible for badge {id}")]
+    NotEligible {
+        id: u64,
+        user: String,
+    },
+
+    #[error("user {user} does not own token {token_id}")]
+    NotTokenOwner {
+        token_id: String,
+        user: String,
+    },
+
+    #[error("token {token_id} has already been used to claim badge {id}")]
+    TokenUsed {
+        id: u64,
+        token_id: String,
+    },
+
+    #[error("voucher nonce {nonce} has already been used for badge {id}")]
+    NonceUsed {
+        id: u64,
+        nonce: u64,
+    },
+
+    #[error("insufficient balance: user {user} holds {balance}, requires {required}")]
+    InsufficientBalance {
+        user: String,
+        balance: Uint128,
+        required: Uint128,
+    },
+
+    #[error("missing proof: expecting {kind}")]
+    MissingProof {
+        kind: String,
+    },
+
+    #[error(
+        "unknown mint rule {found}, expecting by_minter|by_key|by_keys|by_allowlist|by_merkle_root|\
+        by_payment|by_prerequisites|by_oracle|by_collection|by_balance|all|any"
+    )]
     UnknownMintRule {
         found: String,
     },
@@ -88,6 +266,9 @@ pub enum ContractError {
         expect: String,
         found: String,
     },
+
+    #[error("the migration limit must not be zero")]
+    InvalidMigrationLimit,
 }
 
 impl ContractError {
@@ -104,6 +285,54 @@ impl ContractError {
         }
     }
 
+    pub fn key_expired(id: u64) -> Self {
+        ContractError::KeyExpired {
+            id,
+        }
+    }
+
+    pub fn already_allowlisted(id: u64, user: impl Into<String>) -> Self {
+        ContractError::AlreadyAllowlisted {
+            id,
+            user: user.into(),
+        }
+    }
+
+    pub fn not_allowlisted(id: u64, user: impl Into<String>) -> Self {
+        ContractError::NotAllowlisted {
+            id,
+            user: user.into(),
+        }
+    }
+
+    pub fn instance_not_found(id: u64, serial: u64) -> Self {
+        ContractError::InstanceNotFound {
+            id,
+            serial,
+        }
+    }
+
+    pub fn already_revoked(id: u64, serial: u64) -> Self {
+        ContractError::AlreadyRevoked {
+            id,
+            serial,
+        }
+    }
+
+    pub fn blocked(id: u64, user: impl Into<String>) -> Self {
+        ContractError::Blocked {
+            id,
+            user: user.into(),
+        }
+    }
+
+    pub fn no_offer(id: u64, user: impl Into<String>) -> Self {
+        ContractError::NoOffer {
+            id,
+            user: user.into(),
+        }
+    }
+
     pub fn already_claimed(id: u64, user: impl Into<String>) -> Self {
         ContractError::AlreadyClaimed {
             id,
@@ -111,6 +340,72 @@ impl ContractError {
         }
     }
 
+    pub fn insufficient_payment(expected: impl ToString, received: impl ToString) -> Self {
+        ContractError::InsufficientPayment {
+            expected: expected.to_string(),
+            received: received.to_string(),
+        }
+    }
+
+    pub fn missing_prerequisite(id: u64, user: impl Into<String>) -> Self {
+        ContractError::MissingPrerequisite {
+            id,
+            user: user.into(),
+        }
+    }
+
+    pub fn no_prerequisite(user: impl Into<String>) -> Self {
+        ContractError::NoPrerequisite {
+            user: user.into(),
+        }
+    }
+
+    pub fn not_eligible(id: u64, user: impl Into<String>) -> Self {
+        ContractError::NotEligible {
+            id,
+            user: user.into(),
+        }
+    }
+
+    pub fn not_token_owner(token_id: impl Into<String>, user: impl Into<String>) -> Self {
+        ContractError::NotTokenOwner {
+            token_id: token_id.into(),
+            user: user.into(),
+        }
+    }
+
+    pub fn token_used(id: u64, token_id: impl Into<String>) -> Self {
+        ContractError::TokenUsed {
+            id,
+            token_id: token_id.into(),
+        }
+    }
+
+    pub fn nonce_used(id: u64, nonce: u64) -> Self {
+        ContractError::NonceUsed {
+            id,
+            nonce,
+        }
+    }
+
+    pub fn insufficient_balance(
+        user: impl Into<String>,
+        balance: Uint128,
+        required: Uint128,
+    ) -> Self {
+        ContractError::InsufficientBalance {
+            user: user.into(),
+            balance,
+            required,
+        }
+    }
+
+    pub fn missing_proof(kind: impl Into<String>) -> Self {
+        ContractError::MissingProof {
+            kind: kind.into(),
+        }
+    }
+
     pub fn unknown_mint_rule(found: impl Into<String>) -> Self {
         ContractError::UnknownMintRule {
             found: found.into(),
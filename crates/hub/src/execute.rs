@@ -1,21 +1,31 @@
 use std::collections::BTreeSet;
 
-use cosmwasm_std::{to_binary, Addr, DepsMut, Empty, Env, MessageInfo, StdResult, WasmMsg};
-use cw721_base::msg::MintMsg;
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Binary, DepsMut, Env, MessageInfo, Order, StdResult, Storage,
+};
 use sg_metadata::Metadata;
 use sg_std::Response;
 
-use badges::{Badge, FeeRate, MintRule};
+use badges::{
+    hub::{BatchMintResponse, FailedClaim, KeyClaim, SignatureClaim},
+    Badge, FeeRate, KeyInfo, KeyType, MintProof, MintRule,
+};
 
 use crate::{
     error::ContractError,
-    fee::handle_fee,
+    fee::{handle_fee, handle_payment},
     helpers::*,
     query,
     state::*,
 };
 
-pub fn init(deps: DepsMut, developer: Addr, fee_rate: FeeRate) -> StdResult<Response> {
+pub fn init(
+    deps: DepsMut,
+    developer: Addr,
+    fee_rate: FeeRate,
+) -> Result<Response, ContractError> {
+    assert_valid_fee_rate(&fee_rate)?;
+
     DEVELOPER.save(deps.storage, &developer)?;
     BADGE_COUNT.save(deps.storage, &0)?;
     FEE_RATE.save(deps.storage, &fee_rate)?;
@@ -44,7 +54,9 @@ pub fn set_nft(deps: DepsMut, sender_addr: Addr, nft: &str) -> Result<Response,
         .add_attribute("nft", nft))
 }
 
-pub fn set_fee_rate(deps: DepsMut, fee_rate: FeeRate) -> StdResult<Response> {
+pub fn set_fee_rate(deps: DepsMut, fee_rate: FeeRate) -> Result<Response, ContractError> {
+    assert_valid_fee_rate(&fee_rate)?;
+
     FEE_RATE.save(deps.storage, &fee_rate)?;
 
     Ok(Response::new()
@@ -57,10 +69,16 @@ pub fn create_badge(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    badge: Badge,
+    mut badge: Badge,
 ) -> Result<Response, ContractError> {
+    // Ethereum addresses must be stored in the canonical form
+    normalize_rule_keys(&mut badge.rule);
+
+    // the minting schedule must make sense
+    assert_valid_schedule(&badge)?;
+
     // the badge must not have already expired or have a max supply of zero
-    assert_available(&badge, &env.block, 1)?;
+    assert_not_ended(&badge, &env.block)?;
 
     // ensure the creator has paid a sufficient fee
     let fee_rate = FEE_RATE.load(deps.storage)?;
@@ -72,12 +90,12 @@ pub fn create_badge(
         fee_rate.metadata,
     )?;
 
-    // if the badge uses "by key" mint rule, the key must be a valid secp256k1
-    // public key
-    if let MintRule::ByKey(key) = &badge.rule {
-        let bytes = hex::decode(key)?;
-        assert_valid_secp256k1_pubkey(&bytes)?;
-    }
+    // the mint rule's parameters must be valid, e.g. if the badge uses "by key" mint rule, the key
+    // must be a valid secp256k1 public key
+    assert_valid_rule(deps.as_ref(), &badge.rule)?;
+
+    // the per-user claim limit must not allow the rule's proofs to be replayed
+    assert_valid_max_per_user(&badge)?;
 
     let id = BADGE_COUNT.update(deps.storage, |id| StdResult::Ok(id + 1))?;
     BADGES.save(deps.storage, id, &badge)?;
@@ -96,9 +114,7 @@ pub fn edit_badge(
 ) -> Result<Response, ContractError> {
     let mut badge = BADGES.load(deps.storage, id)?;
 
-    if info.sender != badge.manager {
-        return Err(ContractError::NotManager);
-    }
+    assert_manager(&badge, &info.sender)?;
 
     // ensure the manager pays a sufficient fee
     let fee_rate = FEE_RATE.load(deps.storage)?;
@@ -119,54 +135,264 @@ pub fn edit_badge(
         .add_attribute("fee", stringify_funds(&info.funds)))
 }
 
+pub fn edit_badge_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    expiry: Option<u64>,
+    max_supply: Option<u64>,
+    transferrable: Option<bool>,
+) -> Result<Response, ContractError> {
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_manager(&badge, &info.sender)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "badges/hub/edit_badge_config")
+        .add_attribute("id", id.to_string());
+
+    // the minting deadline can only be extended, and only if it hasn't been reached yet.
+    // once a badge has expired, its keys and owners may have already been purged, so it is not
+    // safe to make it available again.
+    if let Some(expiry) = expiry {
+        match badge.expiry {
+            Some(current) if env.block.time.seconds() > current => {
+                return Err(ContractError::Expired);
+            },
+            Some(current) if expiry > current => (),
+            _ => return Err(ContractError::InvalidExpiry),
+        }
+
+        badge.expiry = Some(expiry);
+        res = res.add_attribute("expiry", expiry.to_string());
+    }
+
+    // the max supply can only be lowered, but not below the current supply
+    if let Some(max_supply) = max_supply {
+        if max_supply < badge.current_supply
+            || matches!(badge.max_supply, Some(current) if max_supply >= current)
+        {
+            return Err(ContractError::InvalidMaxSupply);
+        }
+
+        badge.max_supply = Some(max_supply);
+        res = res.add_attribute("max_supply", max_supply.to_string());
+    }
+
+    // a transferrable badge can be made soulbound, but not the other way around
+    if let Some(transferrable) = transferrable {
+        if transferrable && !badge.transferrable {
+            return Err(ContractError::CannotMakeTransferrable);
+        }
+
+        badge.transferrable = transferrable;
+        res = res.add_attribute("transferrable", transferrable.to_string());
+    }
+
+    BADGES.save(deps.storage, id, &badge)?;
+
+    Ok(res)
+}
+
+pub fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_manager(&badge, &info.sender)?;
+
+    badge.paused = paused;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/set_paused")
+        .add_attribute("id", id.to_string())
+        .add_attribute("paused", paused.to_string()))
+}
+
+pub fn transfer_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    new_manager: String,
+) -> Result<Response, ContractError> {
+    let badge = BADGES.load(deps.storage, id)?;
+
+    // only the badge's manager can propose a new manager
+    assert_manager(&badge, &info.sender)?;
+
+    let new_manager_addr = deps.api.addr_validate(&new_manager)?;
+    PENDING_MANAGERS.save(deps.storage, id, &new_manager_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/transfer_manager")
+        .add_attribute("id", id.to_string())
+        .add_attribute("pending_manager", new_manager))
+}
+
+pub fn accept_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    // only the proposed new manager can accept the handover
+    let pending_manager = PENDING_MANAGERS.may_load(deps.storage, id)?;
+    if pending_manager.as_ref() != Some(&info.sender) {
+        return Err(ContractError::NotPendingManager);
+    }
+
+    PENDING_MANAGERS.remove(deps.storage, id);
+
+    let previous_manager = badge.manager.replace(info.sender.clone());
+    BADGES.save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/accept_manager")
+        .add_attribute("id", id.to_string())
+        .add_attribute("previous_manager", stringify_option(previous_manager))
+        .add_attribute("new_manager", info.sender))
+}
+
+pub fn renounce_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    // only the badge's manager can renounce
+    assert_manager(&badge, &info.sender)?;
+
+    // also discard any pending handover, so that it can't be accepted after renouncing
+    PENDING_MANAGERS.remove(deps.storage, id);
+
+    badge.manager = None;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/renounce_manager")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn rotate_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    key: String,
+    key_type: KeyType,
+) -> Result<Response, ContractError> {
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    // only the badge's manager can rotate the key
+    assert_manager(&badge, &info.sender)?;
+
+    // the badge must be of "by key" minting rule
+    if !matches!(badge.rule, MintRule::ByKey { .. }) {
+        return Err(ContractError::wrong_mint_rule("by_key", &badge.rule));
+    }
+
+    // ensure the manager pays a sufficient fee
+    // the new key is charged in full, as keys of the same type have the same size; deducting the
+    // old key would make rotation free
+    let fee_rate = FEE_RATE.load(deps.storage)?;
+    let res = handle_fee(deps.as_ref().storage, &info, None, &key, fee_rate.key)?;
+
+    // the minting deadline must not have been reached
+    // the max supply must not have been reached
+    assert_not_ended(&badge, &env.block)?;
+
+    // the new key must be a valid public key of the specified type
+    let key = normalize_key(&key);
+    let bytes = hex::decode(&key)?;
+    assert_valid_pubkey(key_type, &bytes)?;
+
+    // NOTE: we don't touch the list of users who have already claimed the badge, so that they can't
+    // claim it again using the new key
+    badge.rule = MintRule::ByKey {
+        key: key.clone(),
+        key_type,
+    };
+    BADGES.save(deps.storage, id, &badge)?;
+
+    Ok(res
+        .add_attribute("action", "badges/hub/rotate_key")
+        .add_attribute("id", id.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds))
+        .add_attribute("key", key))
+}
+
 pub fn add_keys(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     id: u64,
     keys: BTreeSet<String>,
+    key_info: KeyInfo,
 ) -> Result<Response, ContractError> {
     let badge = BADGES.load(deps.storage, id)?;
 
     // only the badge's manager can add keys
-    if info.sender != badge.manager {
-        return Err(ContractError::NotManager);
-    }
+    assert_manager(&badge, &info.sender)?;
 
-    // the badge must be of "by keys" minting rule
-    match &badge.rule {
-        MintRule::ByKeys => (),
-        rule => return Err(ContractError::wrong_mint_rule("by_keys", rule)),
+    // the badge must be of "by keys" minting rule, or a composite rule containing it
+    if !badge.rule.uses_keys() {
+        return Err(ContractError::wrong_mint_rule("by_keys", &badge.rule));
     }
 
-    // ensure the manager pays a sufficient fee
+    // ensure the manager pays a sufficient fee, which covers the key info stored with each key
     let fee_rate = FEE_RATE.load(deps.storage)?;
+    let entries = keys.iter().map(|key| (key, &key_info)).collect::<Vec<_>>();
     let res = handle_fee(
         deps.as_ref().storage,
         &info,
         None,
-        &keys,
+        &entries,
         fee_rate.key,
     )?;
 
     // the minting deadline must not have been reached
     // the max supply must not have been reached
-    assert_available(&badge, &env.block, 1)?;
+    // however, keys can be added before minting starts
+    assert_not_ended(&badge, &env.block)?;
+
+    // each key must be usable at least once
+    if key_info.uses == 0 {
+        return Err(ContractError::InvalidKeyUses);
+    }
+
+    // the tier is copied into each instance minted with the key, so it must be reasonably short
+    if key_info.tier.as_ref().is_some_and(|tier| tier.len() > MAX_TIER_LEN) {
+        return Err(ContractError::TierTooLong {
+            max: MAX_TIER_LEN,
+        });
+    }
 
     // save the keys
     keys.iter().try_for_each(|key| -> Result<_, ContractError> {
+        // Ethereum addresses must be stored in the canonical form
+        let key = &normalize_key(key);
+
         // key must be a of valid hex encoding
         let bytes = hex::decode(key)?;
 
-        // key must be a valid secp256k1 public key
-        assert_valid_secp256k1_pubkey(&bytes)?;
+        // key must be a valid public key of the specified type
+        assert_valid_pubkey(key_info.key_type, &bytes)?;
 
         // the key must not already exist
-        if KEYS.insert(deps.storage, (id, key))? {
-            Ok(())
-        } else {
-            Err(ContractError::key_exists(id, key))
+        if KEYS.has(deps.storage, (id, key)) {
+            return Err(ContractError::key_exists(id, key));
         }
+
+        KEYS.save(deps.storage, (id, key), &key_info)?;
+
+        Ok(())
     })?;
 
     Ok(res
@@ -176,6 +402,65 @@ pub fn add_keys(
         .add_attribute("keys_added", keys.len().to_string()))
 }
 
+pub fn revoke_keys(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    keys: BTreeSet<String>,
+) -> Result<Response, ContractError> {
+    let badge = BADGES.load(deps.storage, id)?;
+
+    // only the badge's manager can revoke keys
+    assert_manager(&badge, &info.sender)?;
+
+    // the badge must be of "by keys" minting rule, or a composite rule containing it
+    if !badge.rule.uses_keys() {
+        return Err(ContractError::wrong_mint_rule("by_keys", &badge.rule));
+    }
+
+    let mut keys_revoked = 0u64;
+    for key in &keys {
+        let key = &normalize_key(key);
+        if KEYS.has(deps.storage, (id, key)) {
+            KEYS.remove(deps.storage, (id, key));
+            keys_revoked += 1;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/revoke_keys")
+        .add_attribute("id", id.to_string())
+        .add_attribute("keys_revoked", keys_revoked.to_string()))
+}
+
+pub fn revoke_all_keys(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let badge = BADGES.load(deps.storage, id)?;
+
+    // only the badge's manager can revoke keys
+    assert_manager(&badge, &info.sender)?;
+
+    // the badge must be of "by keys" minting rule, or a composite rule containing it
+    if !badge.rule.uses_keys() {
+        return Err(ContractError::wrong_mint_rule("by_keys", &badge.rule));
+    }
+
+    // same as in `purge_keys`, collect the keys into a Vec first before deleting them
+    let res = query::keys(deps.as_ref(), id, None, limit)?;
+    for key in &res.keys {
+        KEYS.remove(deps.storage, (id, &key.key));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/revoke_all_keys")
+        .add_attribute("id", id.to_string())
+        .add_attribute("keys_revoked", res.keys.len().to_string()))
+}
+
 pub fn purge_keys(
     deps: DepsMut,
     env: Env,
@@ -189,9 +474,12 @@ pub fn purge_keys(
 
     // need to collect the keys into a Vec first before creating a new iterator to delete them
     // because of how Rust works
+    //
+    // NOTE: the tiers stamped on instances minted using these keys are kept, as the NFT contract
+    // needs them to render the instances' traits
     let res = query::keys(deps.as_ref(), id, None, limit)?;
     for key in &res.keys {
-        KEYS.remove(deps.storage, (id, key))?;
+        KEYS.remove(deps.storage, (id, &key.key));
     };
 
     Ok(Response::new()
@@ -200,147 +488,966 @@ pub fn purge_keys(
         .add_attribute("keys_purged", res.keys.len().to_string()))
 }
 
-pub fn purge_owners(
+pub fn add_allowlist(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     id: u64,
-    limit: Option<u32>,
+    users: BTreeSet<String>,
 ) -> Result<Response, ContractError> {
     let badge = BADGES.load(deps.storage, id)?;
 
-    // can only purge user data once the badge becomes unavailable to be minted
-    assert_unavailable(&badge, &env.block)?;
+    // only the badge's manager can add users to the allowlist
+    assert_manager(&badge, &info.sender)?;
 
-    // need to collect the user addresses into a Vec first before creating a new iterator to delete
-    // them because of how Rust works
-    let res = query::owners(deps.as_ref(), id, None, limit)?;
-    for owner in &res.owners {
-        OWNERS.remove(deps.storage, (id, owner))?;
-    };
+    // the badge must be of "by allowlist" minting rule, or a composite rule containing it
+    if !badge.rule.uses_allowlist() {
+        return Err(ContractError::wrong_mint_rule("by_allowlist", &badge.rule));
+    }
 
-    Ok(Response::new()
-        .add_attribute("action", "badges/hub/purge_owners")
+    // ensure the manager pays a sufficient fee, at the same rate as for keys
+    let fee_rate = FEE_RATE.load(deps.storage)?;
+    let res = handle_fee(
+        deps.as_ref().storage,
+        &info,
+        None,
+        &users,
+        fee_rate.key,
+    )?;
+
+    // same as with keys, users can be added before minting starts, but not after it ends
+    assert_not_ended(&badge, &env.block)?;
+
+    // save the users
+    users.iter().try_for_each(|user| -> Result<_, ContractError> {
+        // the user must be a valid address
+        deps.api.addr_validate(user)?;
+
+        // the user must not already be on the allowlist
+        if ALLOWLIST.contains(deps.storage, (id, user)) {
+            return Err(ContractError::already_allowlisted(id, user));
+        }
+
+        ALLOWLIST.insert(deps.storage, (id, user))?;
+
+        Ok(())
+    })?;
+
+    Ok(res
+        .add_attribute("action", "badges/hub/add_allowlist")
         .add_attribute("id", id.to_string())
-        .add_attribute("owners_purged", res.owners.len().to_string()))
+        .add_attribute("fee", stringify_funds(&info.funds))
+        .add_attribute("users_added", users.len().to_string()))
 }
 
-pub fn mint_by_minter(
+pub fn remove_allowlist(
     deps: DepsMut,
-    env: Env,
+    info: MessageInfo,
     id: u64,
-    owners: BTreeSet<String>,
-    sender: Addr,
+    users: BTreeSet<String>,
 ) -> Result<Response, ContractError> {
-    let nft_addr = NFT.load(deps.storage)?;
-    let mut badge = BADGES.load(deps.storage, id)?;
-
-    let amount = owners.len() as u64;
-    let start_serial = badge.current_supply + 1;
+    let badge = BADGES.load(deps.storage, id)?;
 
-    assert_available(&badge, &env.block, amount)?;
-    assert_can_mint_by_minter(&badge, &sender)?;
+    // only the badge's manager can remove users from the allowlist
+    assert_manager(&badge, &info.sender)?;
 
-    badge.current_supply += amount;
-    BADGES.save(deps.storage, id, &badge)?;
+    // the badge must be of "by allowlist" minting rule, or a composite rule containing it
+    if !badge.rule.uses_allowlist() {
+        return Err(ContractError::wrong_mint_rule("by_allowlist", &badge.rule));
+    }
 
-    let msgs = owners
-        .into_iter()
-        .enumerate()
-        .map(|(idx, owner)| -> StdResult<_> {
-            let serial = start_serial + (idx as u64);
-            Ok(WasmMsg::Execute {
-                contract_addr: nft_addr.to_string(),
-                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                    token_id: token_id(id, serial),
-                    owner,
-                    token_uri: None,
-                    extension: None,
-                }))?,
-                funds: vec![],
-            })
-        })
-        .collect::<StdResult<Vec<_>>>()?;
+    let mut users_removed = 0u64;
+    for user in &users {
+        if ALLOWLIST.remove(deps.storage, (id, user))? {
+            users_removed += 1;
+        }
+    }
 
     Ok(Response::new()
-        .add_messages(msgs)
-        .add_attribute("action", "badges/hub/mint_by_minter")
+        .add_attribute("action", "badges/hub/remove_allowlist")
         .add_attribute("id", id.to_string())
-        .add_attribute("amount", amount.to_string()))
+        .add_attribute("users_removed", users_removed.to_string()))
 }
 
-pub fn mint_by_key(
+pub fn purge_allowlist(
     deps: DepsMut,
     env: Env,
     id: u64,
-    owner: String,
-    signature: String,
+    limit: Option<u32>,
 ) -> Result<Response, ContractError> {
-    let nft_addr = NFT.load(deps.storage)?;
-    let mut badge = BADGES.load(deps.storage, id)?;
+    let badge = BADGES.load(deps.storage, id)?;
 
-    assert_available(&badge, &env.block, 1)?;
-    assert_eligible(deps.storage, id, &owner)?;
-    assert_can_mint_by_key(deps.api, id, &badge, &owner, &signature)?;
+    // can only purge the allowlist once the badge becomes unavailable to be minted
+    assert_unavailable(&badge, &env.block)?;
 
-    badge.current_supply += 1;
-    BADGES.save(deps.storage, id, &badge)?;
+    // same as in `purge_keys`, collect the users into a Vec first before deleting them
+    let res = query::allowlist(deps.as_ref(), id, None, limit)?;
+    for user in &res.users {
+        ALLOWLIST.remove(deps.storage, (id, user))?;
+    }
 
-    OWNERS.insert(deps.storage, (id, &owner))?;
-
-    Ok(Response::new()
-        .add_message(WasmMsg::Execute {
-            contract_addr: nft_addr.to_string(),
-            msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                token_id: token_id(id, badge.current_supply),
-                // NOTE: it's possible to avoid cloning and save a liiiittle bit of gas here, simply
-                // by moving this `add_message` after the one `add_attribute` that uses `owner`.
-                // however this makes the code uglier so i don't want to do it.
-                // Stargaze has free gas price anyways (for now at least)
-                owner: owner.clone(),
-                token_uri: None,
-                extension: None,
-            }))?,
-            funds: vec![],
-        })
-        .add_attribute("action", "badges/hub/mint_by_key")
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/purge_allowlist")
         .add_attribute("id", id.to_string())
-        .add_attribute("serial", badge.current_supply.to_string())
-        .add_attribute("recipient", owner))
+        .add_attribute("users_purged", res.users.len().to_string()))
 }
 
-pub fn mint_by_keys(
+pub fn purge_owners(
     deps: DepsMut,
     env: Env,
     id: u64,
-    owner: String,
-    pubkey: String,
-    signature: String,
+    limit: Option<u32>,
 ) -> Result<Response, ContractError> {
-    let nft_addr = NFT.load(deps.storage)?;
-    let mut badge = BADGES.load(deps.storage, id)?;
-
-    assert_available(&badge, &env.block, 1)?;
-    assert_eligible(deps.storage, id, &owner)?;
-    assert_can_mint_by_keys(deps.as_ref(), id, &badge, &owner, &pubkey, &signature)?;
+    let badge = BADGES.load(deps.storage, id)?;
 
-    badge.current_supply += 1;
-    BADGES.save(deps.storage, id, &badge)?;
+    // can only purge user data once the badge becomes unavailable to be minted
+    assert_unavailable(&badge, &env.block)?;
 
-    KEYS.remove(deps.storage, (id, &pubkey))?;
-    OWNERS.insert(deps.storage, (id, &owner))?;
+    // need to collect the user addresses into a Vec first before creating a new iterator to delete
+    // them because of how Rust works
+    let res = query::owners(deps.as_ref(), id, None, limit)?;
+    for owner in &res.owners {
+        OWNERS.remove(deps.storage, (id, owner))?;
+        CLAIM_COUNTS.remove(deps.storage, (id, owner));
+    };
 
     Ok(Response::new()
-        .add_message(WasmMsg::Execute {
-            contract_addr: nft_addr.to_string(),
-            msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
-                token_id: token_id(id, badge.current_supply),
-                owner: owner.clone(),
-                token_uri: None,
-                extension: None,
-            }))?,
-            funds: vec![],
-        })
-        .add_attribute("action", "badges/hub/mint_by_keys")
+        .add_attribute("action", "badges/hub/purge_owners")
+        .add_attribute("id", id.to_string())
+        .add_attribute("owners_purged", res.owners.len().to_string()))
+}
+
+pub fn revoke_instance(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    serial: u64,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let badge = BADGES.load(deps.storage, id)?;
+
+    // only the badge's manager can revoke instances
+    assert_manager(&badge, &info.sender)?;
+
+    // the instance must have been minted, and not already revoked
+    if serial == 0 || serial > badge.current_supply {
+        return Err(ContractError::instance_not_found(id, serial));
+    }
+    if REVOCATIONS.has(deps.storage, (id, serial)) {
+        return Err(ContractError::already_revoked(id, serial));
+    }
+
+    // ensure the manager pays a sufficient fee for storing the reason, if one is given
+    let res = match &reason {
+        Some(reason) => {
+            let fee_rate = FEE_RATE.load(deps.storage)?;
+            handle_fee(deps.as_ref().storage, &info, None, reason, fee_rate.metadata)?
+        },
+        None => Response::new(),
+    };
+
+    // NOTE: the token is not burned, so the badge's supply is left unchanged
+    REVOCATIONS.save(deps.storage, (id, serial), &reason)?;
+
+    Ok(res
+        .add_attribute("action", "badges/hub/revoke_instance")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", serial.to_string())
+        .add_attribute("fee", stringify_funds(&info.funds)))
+}
+
+pub fn block_badge(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    // the badge must exist
+    BADGES.load(deps.storage, id)?;
+
+    BLOCKED_BADGES.insert(deps.storage, (info.sender.as_str(), id))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/block_badge")
+        .add_attribute("id", id.to_string())
+        .add_attribute("user", info.sender))
+}
+
+pub fn unblock_badge(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    BLOCKED_BADGES.remove(deps.storage, (info.sender.as_str(), id))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/unblock_badge")
+        .add_attribute("id", id.to_string())
+        .add_attribute("user", info.sender))
+}
+
+pub fn block_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    manager: String,
+) -> Result<Response, ContractError> {
+    let manager_addr = deps.api.addr_validate(&manager)?;
+
+    BLOCKED_MANAGERS.insert(deps.storage, (info.sender.as_str(), manager_addr.as_str()))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/block_manager")
+        .add_attribute("manager", manager_addr)
+        .add_attribute("user", info.sender))
+}
+
+pub fn unblock_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    manager: String,
+) -> Result<Response, ContractError> {
+    BLOCKED_MANAGERS.remove(deps.storage, (info.sender.as_str(), &manager))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/unblock_manager")
+        .add_attribute("manager", manager)
+        .add_attribute("user", info.sender))
+}
+
+pub fn mint_by_minter(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owners: BTreeSet<String>,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    let amount = owners.len() as u64;
+    let start_serial = badge.current_supply + 1;
+
+    assert_available(&badge, &env.block, amount)?;
+    assert_not_paused(&badge)?;
+    assert_can_mint_by_minter(&badge, &sender)?;
+    for owner in &owners {
+        assert_not_blocked(deps.storage, id, &badge, owner)?;
+    }
+
+    // if the badge requires consent, make offers to the owners instead. the supply is only
+    // updated once an offer is accepted
+    if badge.require_consent {
+        for owner in &owners {
+            OFFERS.insert(deps.storage, (id, owner))?;
+        }
+
+        return Ok(Response::new()
+            .add_attribute("action", "badges/hub/mint_by_minter")
+            .add_attribute("id", id.to_string())
+            .add_attribute("offered", amount.to_string()));
+    }
+
+    badge.current_supply += amount;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    let msgs = owners
+        .iter()
+        .enumerate()
+        .map(|(idx, owner)| mint_msg(&nft_addr, id, start_serial + (idx as u64), owner))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "badges/hub/mint_by_minter")
+        .add_attribute("id", id.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn accept_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    // offers can only be accepted while the badge is available
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+
+    // the sender must have a pending offer
+    if !OFFERS.remove(deps.storage, (id, info.sender.as_str()))? {
+        return Err(ContractError::no_offer(id, info.sender));
+    }
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, info.sender.as_str())?)
+        .add_attribute("action", "badges/hub/accept_offer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", info.sender))
+}
+
+pub fn reject_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // the sender must have a pending offer
+    if !OFFERS.remove(deps.storage, (id, info.sender.as_str()))? {
+        return Err(ContractError::no_offer(id, info.sender));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/reject_offer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("user", info.sender))
+}
+
+pub fn purge_offers(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let badge = BADGES.load(deps.storage, id)?;
+
+    // can only purge offers once the badge becomes unavailable to be minted
+    assert_unavailable(&badge, &env.block)?;
+
+    // same as in `purge_keys`, collect the users into a Vec first before deleting them
+    let res = query::offers(deps.as_ref(), id, None, limit)?;
+    for user in &res.users {
+        OFFERS.remove(deps.storage, (id, user))?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/purge_offers")
+        .add_attribute("id", id.to_string())
+        .add_attribute("offers_purged", res.users.len().to_string()))
+}
+
+pub fn purge_collection_tokens(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let badge = BADGES.load(deps.storage, id)?;
+
+    // can only purge the used tokens once the badge becomes unavailable to be minted
+    assert_unavailable(&badge, &env.block)?;
+
+    // same as in `purge_keys`, collect the token ids into a Vec first before deleting them
+    let limit = limit.unwrap_or(query::DEFAULT_LIMIT).min(query::MAX_LIMIT) as usize;
+    let token_ids = COLLECTION_TOKENS
+        .prefix(id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    for token_id in &token_ids {
+        COLLECTION_TOKENS.remove(deps.storage, (id, token_id))?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/purge_collection_tokens")
+        .add_attribute("id", id.to_string())
+        .add_attribute("tokens_purged", token_ids.len().to_string()))
+}
+
+pub fn purge_nonces(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let badge = BADGES.load(deps.storage, id)?;
+
+    // can only purge the redeemed nonces once the badge becomes unavailable to be minted
+    assert_unavailable(&badge, &env.block)?;
+
+    // same as in `purge_keys`, collect the nonces into a Vec first before deleting them
+    let limit = limit.unwrap_or(query::DEFAULT_LIMIT).min(query::MAX_LIMIT) as usize;
+    let nonces = NONCES
+        .prefix(id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    for nonce in &nonces {
+        NONCES.remove(deps.storage, (id, *nonce))?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/purge_nonces")
+        .add_attribute("id", id.to_string())
+        .add_attribute("nonces_purged", nonces.len().to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn mint_by_voucher(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+    nonce: u64,
+    deadline: u64,
+    pubkey: String,
+    signature: String,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+
+    // if the badge requires consent, redeeming the voucher is how the owner accepts it, so no one
+    // else may redeem it on their behalf
+    if badge.require_consent && sender != owner {
+        return Err(ContractError::ConsentRequired);
+    }
+
+    assert_before_deadline(&env.block, Some(deadline))?;
+    let message =
+        voucher_message(&env.block.chain_id, &env.contract.address, id, &owner, nonce, deadline);
+    assert_can_mint_by_voucher(deps.api, &badge, &pubkey, &message, &signature)?;
+
+    // each voucher can only be redeemed once
+    if NONCES.contains(deps.storage, (id, nonce)) {
+        return Err(ContractError::nonce_used(id, nonce));
+    }
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    NONCES.insert(deps.storage, (id, nonce))?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_voucher")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("nonce", nonce.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn mint_by_key(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+    signature: String,
+    deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    assert_before_deadline(&env.block, deadline)?;
+    let message = claim_message(&env, id, &badge, &owner, deadline);
+    assert_can_mint_by_key(deps.api, &badge, &owner, &message, &signature)?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    record_claim(deps.storage, id, &owner)?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_key")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn mint_by_keys(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+    pubkey: String,
+    signature: String,
+    deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    assert_before_deadline(&env.block, deadline)?;
+    let message = claim_message(&env, id, &badge, &owner, deadline);
+    assert_can_mint_by_keys(
+        deps.as_ref(),
+        &env.block,
+        id,
+        &badge,
+        &owner,
+        &pubkey,
+        &message,
+        &signature,
+    )?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    use_key(deps.storage, id, badge.current_supply, &pubkey)?;
+    record_claim(deps.storage, id, &owner)?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_keys")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn batch_mint_by_key(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    claims: Vec<SignatureClaim>,
+    skip_failures: bool,
+) -> Result<Response, ContractError> {
+    batch_mint(
+        deps,
+        id,
+        claims,
+        skip_failures,
+        "badges/hub/batch_mint_by_key",
+        |claim| &claim.owner,
+        |deps, badge, claim| claim_by_key(deps, &env, id, badge, claim),
+    )
+}
+
+pub fn batch_mint_by_keys(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    claims: Vec<KeyClaim>,
+    skip_failures: bool,
+) -> Result<Response, ContractError> {
+    batch_mint(
+        deps,
+        id,
+        claims,
+        skip_failures,
+        "badges/hub/batch_mint_by_keys",
+        |claim| &claim.owner,
+        |deps, badge, claim| claim_by_keys(deps, &env, id, badge, claim),
+    )
+}
+
+/// Mint a badge to the owners of multiple claims, each verified by `claim_fn`. Unless
+/// `skip_failures` is true, the whole batch fails if any of the claims fails.
+fn batch_mint<C>(
+    mut deps: DepsMut,
+    id: u64,
+    claims: Vec<C>,
+    skip_failures: bool,
+    action: &str,
+    owner_of: impl Fn(&C) -> &String,
+    claim_fn: impl Fn(DepsMut, &mut Badge, &C) -> Result<(), ContractError>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_not_paused(&badge)?;
+
+    let mut res = Response::new();
+    let mut minted = vec![];
+    let mut failed = vec![];
+
+    for claim in claims {
+        let owner = owner_of(&claim).clone();
+        match claim_fn(deps.branch(), &mut badge, &claim) {
+            Ok(()) => {
+                res = res.add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?);
+                minted.push(owner);
+            },
+            Err(err) if skip_failures => {
+                failed.push(FailedClaim {
+                    owner,
+                    error: err.to_string(),
+                });
+            },
+            Err(err) => return Err(err),
+        }
+    }
+
+    BADGES.save(deps.storage, id, &badge)?;
+
+    let data = BatchMintResponse {
+        minted,
+        failed,
+    };
+
+    Ok(res
+        .add_attribute("action", action)
+        .add_attribute("id", id.to_string())
+        .add_attribute("minted", data.minted.len().to_string())
+        .add_attribute("failed", data.failed.len().to_string())
+        .set_data(to_binary(&data)?))
+}
+
+/// Verify a single claim in a batch under the "by key" rule, and if it is valid, update the badge's
+/// supply. All checks are done before any state is written, so that a failed claim can be skipped
+/// without leaving partial changes behind.
+fn claim_by_key(
+    deps: DepsMut,
+    env: &Env,
+    id: u64,
+    badge: &mut Badge,
+    claim: &SignatureClaim,
+) -> Result<(), ContractError> {
+    assert_available(badge, &env.block, 1)?;
+    assert_eligible(deps.storage, id, badge, &claim.owner)?;
+    assert_not_blocked(deps.storage, id, badge, &claim.owner)?;
+    assert_before_deadline(&env.block, claim.deadline)?;
+    let message = claim_message(env, id, badge, &claim.owner, claim.deadline);
+    assert_can_mint_by_key(deps.api, badge, &claim.owner, &message, &claim.signature)?;
+
+    badge.current_supply += 1;
+
+    record_claim(deps.storage, id, &claim.owner)?;
+
+    Ok(())
+}
+
+/// Verify a single claim in a batch under the "by keys" rule, and if it is valid, update the
+/// badge's supply and consume the key. All checks are done before any state is written, so that a
+/// failed claim can be skipped without leaving partial changes behind.
+fn claim_by_keys(
+    deps: DepsMut,
+    env: &Env,
+    id: u64,
+    badge: &mut Badge,
+    claim: &KeyClaim,
+) -> Result<(), ContractError> {
+    assert_available(badge, &env.block, 1)?;
+    assert_eligible(deps.storage, id, badge, &claim.owner)?;
+    assert_not_blocked(deps.storage, id, badge, &claim.owner)?;
+    assert_before_deadline(&env.block, claim.deadline)?;
+    let message = claim_message(env, id, badge, &claim.owner, claim.deadline);
+    assert_can_mint_by_keys(
+        deps.as_ref(),
+        &env.block,
+        id,
+        badge,
+        &claim.owner,
+        &claim.pubkey,
+        &message,
+        &claim.signature,
+    )?;
+
+    badge.current_supply += 1;
+
+    use_key(deps.storage, id, badge.current_supply, &claim.pubkey)?;
+    record_claim(deps.storage, id, &claim.owner)?;
+
+    Ok(())
+}
+
+/// Use a whitelisted key to mint the badge instance of the specified serial: decrement the key's
+/// remaining uses, removing it once there is none left, and record the key's tier, if any, as the
+/// instance's tier.
+fn use_key(store: &mut dyn Storage, id: u64, serial: u64, pubkey: &str) -> StdResult<()> {
+    let pubkey = &normalize_key(pubkey);
+    let mut key_info = KEYS.load(store, (id, pubkey))?;
+
+    key_info.uses -= 1;
+    if key_info.uses == 0 {
+        KEYS.remove(store, (id, pubkey));
+    } else {
+        KEYS.save(store, (id, pubkey), &key_info)?;
+    }
+
+    if let Some(tier) = &key_info.tier {
+        TIERS.save(store, (id, serial), tier)?;
+    }
+
+    Ok(())
+}
+
+pub fn mint_by_allowlist(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, sender.as_str())?;
+    assert_not_blocked(deps.storage, id, &badge, sender.as_str())?;
+    assert_can_mint_by_allowlist(deps.storage, id, &badge, sender.as_str())?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    record_claim(deps.storage, id, sender.as_str())?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, sender.as_str())?)
+        .add_attribute("action", "badges/hub/mint_by_allowlist")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", sender))
+}
+
+pub fn mint_by_merkle_proof(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+    proof: Vec<String>,
+    amount: Option<u64>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    assert_can_mint_by_merkle_proof(deps.storage, id, &badge, &owner, &proof, amount)?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    record_claim(deps.storage, id, &owner)?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_merkle_proof")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn mint_by_payment(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    let (price, recipient, paid) = assert_can_mint_by_payment(&badge, &info)?;
+
+    let res = handle_payment(deps.storage, &info, price, recipient, paid)?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    record_claim(deps.storage, id, &owner)?;
+
+    Ok(res
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_payment")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn mint_by_prerequisites(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    assert_can_mint_by_prerequisites(deps.storage, &deps.querier, &nft_addr, &badge, &owner)?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    record_claim(deps.storage, id, &owner)?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_prerequisites")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn mint_by_oracle(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+    proof: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    assert_can_mint_by_oracle(&deps.querier, id, &badge, &owner, proof)?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    record_claim(deps.storage, id, &owner)?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_oracle")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn mint_by_collection(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    assert_can_mint_by_collection(deps.as_ref(), id, &badge, &owner, &token_id)?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    record_claim(deps.storage, id, &owner)?;
+    COLLECTION_TOKENS.insert(deps.storage, (id, &token_id))?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_collection")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn mint_by_balance(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    assert_can_mint_by_balance(&deps.querier, &badge, &owner)?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    record_claim(deps.storage, id, &owner)?;
+
+    Ok(Response::new()
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint_by_balance")
+        .add_attribute("id", id.to_string())
+        .add_attribute("serial", badge.current_supply.to_string())
+        .add_attribute("recipient", owner))
+}
+
+pub fn mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    owner: String,
+    proofs: Vec<MintProof>,
+    deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    let nft_addr = NFT.load(deps.storage)?;
+    let mut badge = BADGES.load(deps.storage, id)?;
+
+    assert_available(&badge, &env.block, 1)?;
+    assert_not_paused(&badge)?;
+    assert_eligible(deps.storage, id, &badge, &owner)?;
+    assert_not_blocked(deps.storage, id, &badge, &owner)?;
+    assert_before_deadline(&env.block, deadline)?;
+    let message = claim_message(&env, id, &badge, &owner, deadline);
+    let effects = assert_can_mint(
+        deps.as_ref(),
+        &env.block,
+        &info,
+        id,
+        &badge,
+        &badge.rule,
+        &owner,
+        &message,
+        &proofs,
+    )?;
+
+    badge.current_supply += 1;
+    BADGES.save(deps.storage, id, &badge)?;
+
+    let mut res = Response::new();
+    let mut paid = false;
+
+    for effect in effects {
+        match effect {
+            MintEffect::UseKey(pubkey) => {
+                use_key(deps.storage, id, badge.current_supply, &pubkey)?;
+            },
+            MintEffect::UseToken(token_id) => {
+                COLLECTION_TOKENS.insert(deps.storage, (id, &token_id))?;
+            },
+            MintEffect::Pay {
+                price,
+                recipient,
+                paid: amount,
+            } => {
+                let payment_res = handle_payment(deps.storage, &info, &price, &recipient, amount)?;
+                res = res.add_submessages(payment_res.messages).add_events(payment_res.events);
+                paid = true;
+            },
+        }
+    }
+
+    // if no "by payment" rule took effect, e.g. the badge was claimed via another branch of an
+    // "any" rule, refund the funds sent along so that they are not stuck in the contract
+    if !paid && !info.funds.is_empty() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: info.funds.clone(),
+        });
+    }
+
+    record_claim(deps.storage, id, &owner)?;
+
+    Ok(res
+        .add_message(mint_msg(&nft_addr, id, badge.current_supply, &owner)?)
+        .add_attribute("action", "badges/hub/mint")
         .add_attribute("id", id.to_string())
         .add_attribute("serial", badge.current_supply.to_string())
         .add_attribute("recipient", owner))
@@ -1,7 +1,7 @@
-use cosmwasm_std::{to_binary, MessageInfo, Storage, Uint128, Decimal};
-use sg_std::Response;
+use cosmwasm_std::{to_binary, BankMsg, Coin, MessageInfo, Storage, Uint128, Decimal};
+use sg_std::{Response, NATIVE_DENOM};
 
-use crate::{error::ContractError, state::DEVELOPER};
+use crate::{error::ContractError, state::{DEVELOPER, FEE_RATE}};
 
 // TODO: add docs
 pub fn handle_fee<T: serde::Serialize>(
@@ -32,3 +32,49 @@ pub fn handle_fee<T: serde::Serialize>(
 
     Ok(res)
 }
+
+/// Forward the payment for a badge minted under the "by payment" rule to the recipient, and refund
+/// the overpayment, if any, to the sender.
+///
+/// If the badge is priced in ustars, a fraction of the price is charged as protocol fee.
+pub fn handle_payment(
+    store: &dyn Storage,
+    info: &MessageInfo,
+    price: &Coin,
+    recipient: &str,
+    paid: Uint128,
+) -> Result<Response, ContractError> {
+    let fee_rate = FEE_RATE.load(store)?;
+    let fee = if price.denom == NATIVE_DENOM {
+        price.amount * fee_rate.mint
+    } else {
+        Uint128::zero()
+    };
+
+    let mut res = Response::new();
+
+    if !fee.is_zero() {
+        let developer = DEVELOPER.load(store)?;
+        sg1::fair_burn(fee.u128(), Some(developer), &mut res);
+    }
+
+    // forward the rest of the price to the recipient
+    let proceeds = price.amount.checked_sub(fee)?;
+    if !proceeds.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin::new(proceeds.u128(), &price.denom)],
+        });
+    }
+
+    // refund the overpayment, if any, to the sender
+    let refund = paid.checked_sub(price.amount)?;
+    if !refund.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(refund.u128(), &price.denom)],
+        });
+    }
+
+    Ok(res)
+}
@@ -1,30 +1,164 @@
+use std::collections::BTreeSet;
 use std::fmt;
 
-use cosmwasm_std::{Addr, Api, BlockInfo, Deps, Storage, Coin};
+use bech32::{ToBase32, Variant};
+use cosmwasm_std::{
+    to_binary, Addr, Api, Binary, BlockInfo, Coin, Decimal, Deps, Empty, Env, MessageInfo,
+    QuerierWrapper, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw721_base::msg::MintMsg;
+use ripemd::Ripemd160;
 use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 
-use badges::{Badge, MintRule};
+use badges::{nft, oracle, Badge, Denom, FeeRate, KeyType, MessageVersion, MintProof, MintRule};
 
 use crate::{
     error::ContractError,
-    state::{KEYS, OWNERS},
+    state::{
+        ALLOWLIST, BADGES, BLOCKED_BADGES, BLOCKED_MANAGERS, CLAIM_COUNTS, COLLECTION_TOKENS, KEYS,
+        NFT, OWNERS, REVOCATIONS,
+    },
 };
 
 /// Length of a serialized compressed public key
 const ECDSA_COMPRESSED_PUBKEY_LEN: usize = 33;
 /// Length of a serialized uncompressed public key
 const ECDSA_UNCOMPRESSED_PUBKEY_LEN: usize = 65;
+/// Length of a serialized Ed25519 public key
+const ED25519_PUBKEY_LEN: usize = 32;
+/// Length of an Ethereum address
+const ETH_ADDRESS_LEN: usize = 20;
+/// Length of a secp256k1 signature, excluding the recovery id
+const ECDSA_SIGNATURE_LEN: usize = 64;
+/// Length of a SHA-256 hash
+const SHA256_HASH_LEN: usize = 32;
+/// Maximum length of a key's tier, which is copied into each badge instance minted with the key
+pub const MAX_TIER_LEN: usize = 32;
 
 /// Each NFT's token id is simply the badge id and the serial separated by a pipe.
 pub fn token_id(id: u64, serial: u64) -> String {
     format!("{}|{}", id, serial)
 }
 
+/// The message to be sent to the NFT contract to mint the badge instance of the specified serial
+pub fn mint_msg(nft_addr: &Addr, id: u64, serial: u64, owner: &str) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+            token_id: token_id(id, serial),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        }))?,
+        funds: vec![],
+    })
+}
+
+/// Number of the user's tokens to fetch per query when looking for an instance of a badge
+const HOLDINGS_PAGE_LIMIT: u32 = 10;
+
+/// Query the NFT contract whether the user holds at least one instance of the specified badge that
+/// has not been revoked.
+///
+/// Token ids are in the format `{id}|{serial}`, so the user's tokens that come after `{id}|` in
+/// lexicographical order are instances of the badge for as long as they start with `{id}|`.
+/// Revoked instances are skipped, paginating through the user's tokens if necessary.
+pub fn holds_badge(
+    store: &dyn Storage,
+    querier: &QuerierWrapper,
+    nft_addr: &Addr,
+    id: u64,
+    user: &str,
+) -> StdResult<bool> {
+    let prefix = format!("{}|", id);
+    let mut start_after = prefix.clone();
+
+    loop {
+        let res: nft::TokensResponse = querier.query_wasm_smart(
+            nft_addr,
+            &nft::QueryMsg::Tokens {
+                owner: user.to_string(),
+                start_after: Some(start_after),
+                limit: Some(HOLDINGS_PAGE_LIMIT),
+            },
+        )?;
+
+        for token_id in &res.tokens {
+            let serial = match token_id.strip_prefix(&prefix) {
+                Some(serial) => serial,
+                None => return Ok(false),
+            };
+
+            match serial.parse() {
+                Ok(serial) if REVOCATIONS.has(store, (id, serial)) => continue,
+                _ => return Ok(true),
+            }
+        }
+
+        match res.tokens.last() {
+            Some(token_id) if res.tokens.len() == HOLDINGS_PAGE_LIMIT as usize => {
+                start_after = token_id.clone();
+            },
+            _ => return Ok(false),
+        }
+    }
+}
+
 /// The message the user needs to sign to claim the badge under "by key" or "by keys" rule
 pub fn message(id: u64, user: impl fmt::Display) -> String {
     format!("claim badge {} for user {}", id, user)
 }
 
+/// The v2 claim message, which additionally specifies the chain and the Hub contract, so that the
+/// signature can't be replayed elsewhere, and optionally a deadline.
+pub fn message_v2(
+    chain_id: &str,
+    hub: impl fmt::Display,
+    id: u64,
+    user: impl fmt::Display,
+    deadline: Option<u64>,
+) -> String {
+    let deadline = deadline.map(|deadline| deadline.to_string()).unwrap_or_else(|| "none".into());
+    format!(
+        "badges claim message v2\nchain: {}\nhub: {}\nbadge: {}\nuser: {}\ndeadline: {}",
+        chain_id, hub, id, user, deadline,
+    )
+}
+
+/// The message the user needs to sign to claim the badge, in the format used by the badge
+pub fn claim_message(
+    env: &Env,
+    id: u64,
+    badge: &Badge,
+    user: &str,
+    deadline: Option<u64>,
+) -> String {
+    match badge.message_version {
+        MessageVersion::Legacy => message(id, user),
+        MessageVersion::V2 => {
+            message_v2(&env.block.chain_id, &env.contract.address, id, user, deadline)
+        },
+    }
+}
+
+/// The message the minter of a badge under the "by minter" rule signs to issue a voucher, which the
+/// owner can then redeem to mint the badge themselves. It specifies the chain and the Hub contract,
+/// so that the voucher can't be redeemed elsewhere.
+pub fn voucher_message(
+    chain_id: &str,
+    hub: impl fmt::Display,
+    id: u64,
+    owner: impl fmt::Display,
+    nonce: u64,
+    deadline: u64,
+) -> String {
+    format!(
+        "badges voucher\nchain: {}\nhub: {}\nbadge: {}\nowner: {}\nnonce: {}\ndeadline: {}",
+        chain_id, hub, id, owner, nonce, deadline,
+    )
+}
+
 /// The hash function to be used to sign a message before signing it. Here we use SHA256.
 /// https://docs.rs/sha2/latest/sha2/#usage
 pub fn hash(msg: &str) -> Vec<u8> {
@@ -33,6 +167,109 @@ pub fn hash(msg: &str) -> Vec<u8> {
     hasher.finalize().to_vec()
 }
 
+/// The ADR-036 sign doc that a Cosmos wallet's `signArbitrary` method signs when asked to sign the
+/// message on behalf of the signer. The JSON is in canonical form, i.e. with keys sorted and
+/// without whitespaces, which is what the wallet serializes before hashing.
+/// https://github.com/cosmos/cosmos-sdk/blob/main/docs/architecture/adr-036-arbitrary-signature.md
+pub fn adr036_sign_doc(signer: &str, message: &str) -> String {
+    format!(
+        concat!(
+            r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","#,
+            r#""msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"#,
+            r#""sequence":"0"}}"#,
+        ),
+        Binary::from(message.as_bytes()).to_base64(),
+        signer,
+    )
+}
+
+/// The address of the Cosmos account controlled by a compressed secp256k1 pubkey, i.e. the bech32
+/// encoding of `ripemd160(sha256(pubkey))`. The address uses the same bech32 prefix as `user`.
+pub fn derive_address(pubkey: &[u8], user: &str) -> Result<String, ContractError> {
+    let (hrp, _, _) = bech32::decode(user)?;
+    let pubkey_hash = Ripemd160::digest(Sha256::digest(pubkey));
+    Ok(bech32::encode(&hrp, pubkey_hash.to_base32(), Variant::Bech32)?)
+}
+
+/// Normalize a key so that it is stored and looked up the same way regardless of how it is written.
+/// Ethereum addresses, i.e. keys of 20 bytes, are stripped of the `0x` prefix if any, and
+/// lowercased. No other type of key has this length, so other keys are returned unchanged.
+pub fn normalize_key(key: &str) -> String {
+    let stripped = key.strip_prefix("0x").or_else(|| key.strip_prefix("0X")).unwrap_or(key);
+    if stripped.len() == ETH_ADDRESS_LEN * 2 {
+        stripped.to_lowercase()
+    } else {
+        key.to_string()
+    }
+}
+
+/// Normalize the keys of the "by key" rule, or of the "by key" sub-rules of a composite rule.
+pub fn normalize_rule_keys(rule: &mut MintRule) {
+    match rule {
+        MintRule::ByKey {
+            key,
+            ..
+        } => *key = normalize_key(key),
+        MintRule::All(rules) | MintRule::Any(rules) => {
+            rules.iter_mut().for_each(normalize_rule_keys)
+        },
+        _ => (),
+    }
+}
+
+/// The hash that an Ethereum wallet's `personal_sign` method signs, as specified by EIP-191:
+/// `keccak256("\x19Ethereum Signed Message:\n" + len(message) + message)`
+pub fn eip191_hash(message: &str) -> Vec<u8> {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+    hasher.update(message);
+    hasher.finalize().to_vec()
+}
+
+/// Recover the Ethereum address that produced a `personal_sign` signature of the message.
+///
+/// The signature consists of `r` and `s` followed by the recovery id `v`, which is either 0 or 1,
+/// or 27 or 28 as returned by most wallets.
+pub fn recover_eth_address(
+    api: &dyn Api,
+    message: &str,
+    signature: &[u8],
+) -> Result<Vec<u8>, ContractError> {
+    let (rs, v) = match signature.split_last() {
+        Some((v, rs)) if rs.len() == ECDSA_SIGNATURE_LEN => (rs, *v),
+        _ => return Err(ContractError::InvalidSignature),
+    };
+
+    let recovery_param = match v {
+        0 | 1 => v,
+        27 | 28 => v - 27,
+        _ => return Err(ContractError::InvalidSignature),
+    };
+
+    let pubkey = api
+        .secp256k1_recover_pubkey(&eip191_hash(message), rs, recovery_param)
+        .map_err(|_| ContractError::InvalidSignature)?;
+
+    // the address is the last 20 bytes of the keccak256 hash of the uncompressed pubkey, excluding
+    // its 0x04 prefix
+    let pubkey_hash = Keccak256::digest(&pubkey[1..]);
+    Ok(pubkey_hash[(pubkey_hash.len() - ETH_ADDRESS_LEN)..].to_vec())
+}
+
+/// Hash a pair of nodes in a Merkle tree to get their parent node. The two nodes are sorted before
+/// being hashed, so that proofs don't need to specify whether each sibling is on the left or right.
+pub fn hash_pair(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let (left, right) = if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().to_vec()
+}
+
 /// A helper function to help casting Option to String
 pub fn stringify_option(opt: Option<impl fmt::Display>) -> String {
     opt.map_or_else(|| "undefined".to_string(), |value| value.to_string())
@@ -53,35 +290,66 @@ pub fn stringify_funds(funds: &[Coin]) -> String {
         .join(",")
 }
 
-/// This is basically a wrapper of `api.secp256k1_verify`, but instead of taking raw bytes in the
-/// form of `&[u8]`, it takes the pubkey and signature as hex-encoded strings, and the original
-/// message before hashing.
+/// This is basically a wrapper of `api.secp256k1_verify` and `api.ed25519_verify`, but instead of
+/// taking raw bytes in the form of `&[u8]`, it takes the pubkey and signature as hex-encoded
+/// strings, and the original message before hashing.
+///
+/// For ADR-036 keys, the signer in the sign doc is the address of the key, derived using the same
+/// bech32 prefix as the address of `owner`, i.e. the user the badge is to be minted to.
+///
+/// For Ethereum keys, `pubkey` is the hex-encoded address, which must match the address recovered
+/// from the signature.
 pub fn assert_valid_signature(
     api: &dyn Api,
+    key_type: KeyType,
     pubkey: &str,
+    owner: &str,
     message: &str,
     signature: &str,
 ) -> Result<(), ContractError> {
-    let msg_hash_bytes = hash(message);
-    let key_bytes = hex::decode(pubkey)?;
+    let key_bytes = hex::decode(normalize_key(pubkey))?;
     let sig_bytes = hex::decode(signature)?;
 
-    if api.secp256k1_verify(&msg_hash_bytes, &sig_bytes, &key_bytes)? {
+    let valid = match key_type {
+        KeyType::Secp256k1 => api.secp256k1_verify(&hash(message), &sig_bytes, &key_bytes)?,
+        KeyType::Ed25519 => api.ed25519_verify(message.as_bytes(), &sig_bytes, &key_bytes)?,
+        KeyType::Adr036 => {
+            let signer = derive_address(&key_bytes, owner)?;
+            let sign_doc = adr036_sign_doc(&signer, message);
+            api.secp256k1_verify(&hash(&sign_doc), &sig_bytes, &key_bytes)?
+        },
+        KeyType::Ethereum => recover_eth_address(api, message, &sig_bytes)? == key_bytes,
+    };
+
+    if valid {
         Ok(())
     } else {
         Err(ContractError::InvalidSignature)
     }
 }
 
+/// Assert that the fee rate is valid, i.e. the protocol fee charged on paid mints does not exceed
+/// the price.
+pub fn assert_valid_fee_rate(fee_rate: &FeeRate) -> Result<(), ContractError> {
+    if fee_rate.mint > Decimal::one() {
+        return Err(ContractError::InvalidMintFeeRate);
+    }
+
+    Ok(())
+}
+
 // Assert the badge is available to be minted.
-// Throw an error if the mint deadline or the max supply has been reached.
+// Throw an error if the mint deadline or the max supply has been reached, if minting has not started
+// yet, or if the badge has minting phases but none of them is ongoing.
 pub fn assert_available(
     badge: &Badge,
     block: &BlockInfo,
     amount: u64,
 ) -> Result<(), ContractError> {
+    let now = block.time.seconds();
+
     if let Some(expiry) = badge.expiry {
-        if block.time.seconds() > expiry {
+        if now > expiry {
             return Err(ContractError::Expired);
         }
     }
@@ -92,24 +360,184 @@ pub fn assert_available(
         }
     }
 
+    if let Some(start) = badge.start {
+        if now < start {
+            return Err(ContractError::NotStarted);
+        }
+    }
+
+    if !badge.phases.is_empty() {
+        match badge.phases.iter().find(|phase| phase.is_active(now)) {
+            Some(phase) => {
+                if let Some(max_supply) = phase.max_supply {
+                    if badge.current_supply + amount > max_supply {
+                        return Err(ContractError::PhaseSoldOut);
+                    }
+                }
+            },
+            None => {
+                // phases are sorted, so if the last one has ended, all of them have
+                let ended = matches!(badge.phases.last(), Some(phase) if phase.has_ended(now));
+                return Err(if ended {
+                    ContractError::Expired
+                } else {
+                    ContractError::NotStarted
+                });
+            },
+        }
+    }
+
+    Ok(())
+}
+
+// Assert the badge has not permanently become unavailable, i.e. it has neither expired nor sold out.
+// Unlike `assert_available`, this does not require minting to have started, or to be in a phase.
+// A badge with phases is considered sold out if none of the ongoing or future phases has supply
+// left, as the phases' supply caps are cumulative.
+pub fn assert_not_ended(badge: &Badge, block: &BlockInfo) -> Result<(), ContractError> {
+    if let Err(err @ (ContractError::Expired | ContractError::SoldOut)) =
+        assert_available(badge, block, 1)
+    {
+        return Err(err);
+    }
+
+    if !badge.phases.is_empty() {
+        let now = block.time.seconds();
+        let can_mint = badge
+            .phases
+            .iter()
+            .filter(|phase| !phase.has_ended(now))
+            .any(|phase| !matches!(phase.max_supply, Some(max) if badge.current_supply >= max));
+        if !can_mint {
+            return Err(ContractError::SoldOut);
+        }
+    }
+
     Ok(())
 }
 
-// Assert the badge it NOT available to be minted. Throw an error if it is available.
+// Assert the badge it NOT available to be minted, and never will be again. Throw an error if it is
+// available, or may become available later (e.g. minting has not started yet).
 pub fn assert_unavailable(badge: &Badge, block: &BlockInfo) -> Result<(), ContractError> {
-    match assert_available(badge, block, 1) {
+    match assert_not_ended(badge, block) {
         Ok(_) => Err(ContractError::Available),
         Err(_) => Ok(()),
     }
 }
 
-/// Assert that an account has not already minted a badge.
-pub fn assert_eligible(store: &dyn Storage, id: u64, user: &str) -> Result<(), ContractError> {
-    if !OWNERS.contains(store, (id, user)) {
+/// Assert that the badge's minting has not been paused by the manager.
+pub fn assert_not_paused(badge: &Badge) -> Result<(), ContractError> {
+    if badge.paused {
+        Err(ContractError::Paused)
+    } else {
         Ok(())
+    }
+}
+
+/// Assert that the badge's minting schedule makes sense: minting must not end before it starts, and
+/// phases must be sorted by time and not overlap. Only the last phase may be open-ended.
+pub fn assert_valid_schedule(badge: &Badge) -> Result<(), ContractError> {
+    if let (Some(start), Some(expiry)) = (badge.start, badge.expiry) {
+        if start > expiry {
+            return Err(ContractError::InvalidSchedule);
+        }
+    }
+
+    for phase in &badge.phases {
+        if let Some(expiry) = phase.expiry {
+            if phase.start > expiry {
+                return Err(ContractError::InvalidSchedule);
+            }
+        }
+    }
+
+    for pair in badge.phases.windows(2) {
+        match pair[0].expiry {
+            Some(expiry) if expiry < pair[1].start => (),
+            _ => return Err(ContractError::InvalidSchedule),
+        }
+    }
+
+    Ok(())
+}
+
+/// Assert that the sender is the badge's manager.
+///
+/// If the manager has renounced their power, this always fails.
+pub fn assert_manager(badge: &Badge, sender: &Addr) -> Result<(), ContractError> {
+    if badge.manager.as_ref() != Some(sender) {
+        Err(ContractError::NotManager)
     } else {
-        Err(ContractError::already_claimed(id, user))
+        Ok(())
+    }
+}
+
+/// Assert that an account has not already minted a badge.
+pub fn assert_eligible(
+    store: &dyn Storage,
+    id: u64,
+    badge: &Badge,
+    user: &str,
+) -> Result<(), ContractError> {
+    match badge.max_per_user {
+        Some(max_per_user) if claim_count(store, id, user)? >= max_per_user => {
+            Err(ContractError::already_claimed(id, user))
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Assert that the user has not blocked the badge, either by its id, by its manager, or by the
+/// minter of its "by minter" rule, if any.
+///
+/// NOTE: Blocks are by address, so a manager can evade them by handing the badge over to a new
+/// address using `transfer_manager`. Blocking the badge by its id is not affected by this.
+pub fn assert_not_blocked(
+    store: &dyn Storage,
+    id: u64,
+    badge: &Badge,
+    user: &str,
+) -> Result<(), ContractError> {
+    let manager_blocked = badge
+        .manager
+        .as_ref()
+        .is_some_and(|manager| BLOCKED_MANAGERS.contains(store, (user, manager.as_str())));
+
+    if manager_blocked
+        || minter_blocked(store, &badge.rule, user)
+        || BLOCKED_BADGES.contains(store, (user, id))
+    {
+        return Err(ContractError::blocked(id, user));
     }
+
+    Ok(())
+}
+
+/// Whether the user has blocked the minter of the "by minter" rule, or of any "by minter" sub-rule
+/// of a composite rule.
+fn minter_blocked(store: &dyn Storage, rule: &MintRule, user: &str) -> bool {
+    match rule {
+        MintRule::ByMinter(minter) => BLOCKED_MANAGERS.contains(store, (user, minter)),
+        MintRule::All(rules) | MintRule::Any(rules) => {
+            rules.iter().any(|rule| minter_blocked(store, rule, user))
+        },
+        _ => false,
+    }
+}
+
+/// The number of instances of a badge the user has claimed
+pub fn claim_count(store: &dyn Storage, id: u64, user: &str) -> StdResult<u64> {
+    match CLAIM_COUNTS.may_load(store, (id, user))? {
+        Some(count) => Ok(count),
+        None => Ok(OWNERS.contains(store, (id, user)) as u64),
+    }
+}
+
+/// Record that the user has claimed an instance of a badge
+pub fn record_claim(store: &mut dyn Storage, id: u64, user: &str) -> StdResult<()> {
+    let count = claim_count(store, id, user)?;
+    OWNERS.insert(store, (id, user))?;
+    CLAIM_COUNTS.save(store, (id, user), &(count + 1))
 }
 
 /// Assert that a badge indeed uses the "by minter" rule, and that the sender is the minter.
@@ -126,36 +554,76 @@ pub fn assert_can_mint_by_minter(badge: &Badge, sender: &Addr) -> Result<(), Con
     }
 }
 
+/// Assert that a badge indeed uses the "by minter" rule, that the pubkey belongs to the minter, and
+/// that the signature was produced by signing the voucher message (see `voucher_message`) using
+/// the minter's privkey.
+pub fn assert_can_mint_by_voucher(
+    api: &dyn Api,
+    badge: &Badge,
+    pubkey: &str,
+    message: &str,
+    signature: &str,
+) -> Result<(), ContractError> {
+    match &badge.rule {
+        MintRule::ByMinter(minter) => {
+            // the minter's address is derived from the pubkey in the same way a Cosmos wallet does
+            let key_bytes = hex::decode(pubkey)?;
+            if derive_address(&key_bytes, minter)? != *minter {
+                return Err(ContractError::NotMinter);
+            }
+
+            assert_valid_signature(api, KeyType::Secp256k1, pubkey, minter, message, signature)
+        },
+        rule => Err(ContractError::wrong_mint_rule("by_minter", rule)),
+    }
+}
+
+/// Assert that the deadline of a signed claim message, if any, has not passed.
+pub fn assert_before_deadline(
+    block: &BlockInfo,
+    deadline: Option<u64>,
+) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if block.time.seconds() > deadline => Err(ContractError::DeadlinePassed),
+        _ => Ok(()),
+    }
+}
+
 /// Assert that a badge indeed uses the "by key" rule, and the signature was produced by signing the
-/// correct message with the correct privkey.
+/// claim message (see `claim_message`) with the correct privkey.
 pub fn assert_can_mint_by_key(
     api: &dyn Api,
-    id: u64,
     badge: &Badge,
     owner: &str,
+    message: &str,
     signature: &str,
 ) -> Result<(), ContractError> {
     // the badge must use the "by key" minting rule
-    let pubkey = match &badge.rule {
-        MintRule::ByKey(key) => key,
+    let (pubkey, key_type) = match &badge.rule {
+        MintRule::ByKey {
+            key,
+            key_type,
+        } => (key, *key_type),
         rule => return Err(ContractError::wrong_mint_rule("by_key", rule)),
     };
 
     // the signature must be valid
-    let message = message(id, owner);
-    assert_valid_signature(api, pubkey, &message, signature)?;
+    assert_valid_signature(api, key_type, pubkey, owner, message, signature)?;
 
     Ok(())
 }
 
 /// Assert that a badge indeed uses the "by keys" rule, and that the signature was produced by
-/// signing the correct message using a whitelisted privkey.
+/// signing the claim message (see `claim_message`) using a whitelisted, unexpired privkey.
+#[allow(clippy::too_many_arguments)]
 pub fn assert_can_mint_by_keys(
     deps: Deps,
+    block: &BlockInfo,
     id: u64,
     badge: &Badge,
     owner: &str,
     pubkey: &str,
+    message: &str,
     signature: &str,
 ) -> Result<(), ContractError> {
     // the badge must use the "by keys" minting rule
@@ -165,17 +633,657 @@ pub fn assert_can_mint_by_keys(
     }
 
     // the key must be whitelisted
-    if !KEYS.contains(deps.storage, (id, pubkey)) {
-        return Err(ContractError::key_does_not_exist(id));
+    let key_info = KEYS
+        .may_load(deps.storage, (id, &normalize_key(pubkey)))?
+        .ok_or_else(|| ContractError::key_does_not_exist(id))?;
+
+    // the key must not have expired
+    if matches!(key_info.expiry, Some(expiry) if block.time.seconds() > expiry) {
+        return Err(ContractError::key_expired(id));
     }
 
     // the signature must be valid
-    let message = message(id, owner);
-    assert_valid_signature(deps.api, pubkey, &message, signature)?;
+    assert_valid_signature(deps.api, key_info.key_type, pubkey, owner, message, signature)?;
+
+    Ok(())
+}
+
+/// Assert that a badge indeed uses the "by allowlist" rule, and that the user is on its allowlist.
+pub fn assert_can_mint_by_allowlist(
+    store: &dyn Storage,
+    id: u64,
+    badge: &Badge,
+    user: &str,
+) -> Result<(), ContractError> {
+    // the badge must use the "by allowlist" minting rule
+    match &badge.rule {
+        MintRule::ByAllowlist => (),
+        rule => return Err(ContractError::wrong_mint_rule("by_allowlist", rule)),
+    }
+
+    // the user must be on the allowlist
+    if ALLOWLIST.contains(store, (id, user)) {
+        Ok(())
+    } else {
+        Err(ContractError::not_allowlisted(id, user))
+    }
+}
+
+/// Assert that a badge indeed uses the "by merkle root" rule, that the proof shows the owner's
+/// address, along with the amount if one is specified, is a leaf of the Merkle tree, and that the
+/// owner has claimed fewer instances than the amount.
+///
+/// Merkle proofs are not secret; anyone with the list of leaves can compute them. It is therefore
+/// the amount committed to in the leaf, which defaults to one, that bounds how many times a proof
+/// can be used.
+pub fn assert_can_mint_by_merkle_proof(
+    store: &dyn Storage,
+    id: u64,
+    badge: &Badge,
+    owner: &str,
+    proof: &[String],
+    amount: Option<u64>,
+) -> Result<(), ContractError> {
+    // the badge must use the "by merkle root" minting rule
+    let root = match &badge.rule {
+        MintRule::ByMerkleRoot {
+            root,
+        } => root,
+        rule => return Err(ContractError::wrong_mint_rule("by_merkle_root", rule)),
+    };
+
+    // walk up the tree, from the owner's leaf to the root
+    let leaf = match amount {
+        Some(amount) => hash(&format!("{owner}:{amount}")),
+        None => hash(owner),
+    };
+    let computed_root = proof.iter().try_fold(leaf, |node, sibling| {
+        let sibling_bytes = hex::decode(sibling)?;
+        Ok::<_, ContractError>(hash_pair(&node, &sibling_bytes))
+    })?;
+
+    if computed_root != hex::decode(root)? {
+        return Err(ContractError::InvalidMerkleProof);
+    }
+
+    // the owner must not have used up the amount
+    if claim_count(store, id, owner)? >= amount.unwrap_or(1) {
+        return Err(ContractError::already_claimed(id, owner));
+    }
 
     Ok(())
 }
 
+/// Assert that a badge indeed uses the "by payment" rule, and that the sender has paid no less than
+/// the price, in the correct denom. Return the price, the recipient, and the amount paid.
+///
+/// The funds must be exactly one coin of the price's denom; any other denom is rejected. Paying
+/// more than the price is accepted rather than rejected, as the overpayment is refunded, so that
+/// frontends can add a margin without risking a failed transaction.
+pub fn assert_can_mint_by_payment<'a>(
+    badge: &'a Badge,
+    info: &MessageInfo,
+) -> Result<(&'a Coin, &'a str, Uint128), ContractError> {
+    // the badge must use the "by payment" minting rule
+    let (price, recipient) = match &badge.rule {
+        MintRule::ByPayment {
+            price,
+            recipient,
+        } => (price, recipient),
+        rule => return Err(ContractError::wrong_mint_rule("by_payment", rule)),
+    };
+
+    // the sender must send exactly one coin, which is of the correct denom
+    let paid = cw_utils::must_pay(info, &price.denom)?;
+
+    if paid < price.amount {
+        return Err(ContractError::insufficient_payment(
+            price,
+            Coin::new(paid.u128(), &price.denom),
+        ));
+    }
+
+    Ok((price, recipient, paid))
+}
+
+/// Assert that a badge indeed uses the "by prerequisites" rule, and that the owner holds the
+/// prerequisite badges.
+pub fn assert_can_mint_by_prerequisites(
+    store: &dyn Storage,
+    querier: &QuerierWrapper,
+    nft_addr: &Addr,
+    badge: &Badge,
+    owner: &str,
+) -> Result<(), ContractError> {
+    // the badge must use the "by prerequisites" minting rule
+    let (all_of, any_of) = match &badge.rule {
+        MintRule::ByPrerequisites {
+            all_of,
+            any_of,
+        } => (all_of, any_of),
+        rule => return Err(ContractError::wrong_mint_rule("by_prerequisites", rule)),
+    };
+
+    // the owner must hold every one of the `all_of` badges
+    for id in all_of {
+        if !holds_badge(store, querier, nft_addr, *id, owner)? {
+            return Err(ContractError::missing_prerequisite(*id, owner));
+        }
+    }
+
+    // the owner must hold at least one of the `any_of` badges, if any is specified
+    if !any_of.is_empty() {
+        for id in any_of {
+            if holds_badge(store, querier, nft_addr, *id, owner)? {
+                return Ok(());
+            }
+        }
+        return Err(ContractError::no_prerequisite(owner));
+    }
+
+    Ok(())
+}
+
+/// Assert that a badge indeed uses the "by oracle" rule, and that the oracle deems the owner
+/// eligible.
+pub fn assert_can_mint_by_oracle(
+    querier: &QuerierWrapper,
+    id: u64,
+    badge: &Badge,
+    owner: &str,
+    proof: Option<Binary>,
+) -> Result<(), ContractError> {
+    // the badge must use the "by oracle" minting rule
+    let contract = match &badge.rule {
+        MintRule::ByOracle {
+            contract,
+        } => contract,
+        rule => return Err(ContractError::wrong_mint_rule("by_oracle", rule)),
+    };
+
+    // the oracle must deem the owner eligible
+    let res: oracle::IsEligibleResponse = querier.query_wasm_smart(
+        contract,
+        &oracle::QueryMsg::IsEligible {
+            badge_id: id,
+            user: owner.to_string(),
+            proof,
+        },
+    )?;
+
+    if res.eligible {
+        Ok(())
+    } else {
+        Err(ContractError::not_eligible(id, owner))
+    }
+}
+
+/// Assert that a badge indeed uses the "by collection" rule, that the owner owns the token in the
+/// collection, and that the token hasn't already been used to claim this badge.
+pub fn assert_can_mint_by_collection(
+    deps: Deps,
+    id: u64,
+    badge: &Badge,
+    owner: &str,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    // the badge must use the "by collection" minting rule
+    let collection = match &badge.rule {
+        MintRule::ByCollection {
+            collection,
+        } => collection,
+        rule => return Err(ContractError::wrong_mint_rule("by_collection", rule)),
+    };
+
+    // the token must not have already been used
+    if COLLECTION_TOKENS.contains(deps.storage, (id, token_id)) {
+        return Err(ContractError::token_used(id, token_id));
+    }
+
+    // the owner must own the token. sg721 query messages are a superset of cw721's, so they can
+    // be used to query any cw721 collection
+    let res: nft::OwnerOfResponse = deps.querier.query_wasm_smart(
+        collection,
+        &nft::QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        },
+    )?;
+
+    if res.owner == owner {
+        Ok(())
+    } else {
+        Err(ContractError::not_token_owner(token_id, owner))
+    }
+}
+
+/// Query the user's balance of a native coin or a cw20 token
+pub fn query_balance(querier: &QuerierWrapper, denom: &Denom, user: &str) -> StdResult<Uint128> {
+    match denom {
+        Denom::Native(denom) => querier.query_balance(user, denom).map(|coin| coin.amount),
+        Denom::Cw20(contract) => {
+            let res: cw20::BalanceResponse = querier.query_wasm_smart(
+                contract,
+                &cw20::Cw20QueryMsg::Balance {
+                    address: user.to_string(),
+                },
+            )?;
+            Ok(res.balance)
+        },
+    }
+}
+
+/// Assert that a badge indeed uses the "by balance" rule, and that the owner holds no less than
+/// the required amount of the token.
+pub fn assert_can_mint_by_balance(
+    querier: &QuerierWrapper,
+    badge: &Badge,
+    owner: &str,
+) -> Result<(), ContractError> {
+    // the badge must use the "by balance" minting rule
+    let (denom, amount) = match &badge.rule {
+        MintRule::ByBalance {
+            denom,
+            amount,
+        } => (denom, amount),
+        rule => return Err(ContractError::wrong_mint_rule("by_balance", rule)),
+    };
+
+    // the owner must hold a sufficient amount of the token
+    let balance = query_balance(querier, denom, owner)?;
+
+    if balance >= *amount {
+        Ok(())
+    } else {
+        Err(ContractError::insufficient_balance(owner, balance, *amount))
+    }
+}
+
+/// A state change to be applied upon minting, once the mint rule is satisfied
+#[derive(Debug, PartialEq)]
+pub enum MintEffect {
+    /// Use a whitelisted pubkey once
+    UseKey(String),
+
+    /// Mark a token of the collection as used
+    UseToken(String),
+
+    /// Forward the payment to the recipient, and refund the overpayment
+    Pay {
+        price: Coin,
+        recipient: String,
+        paid: Uint128,
+    },
+}
+
+/// Find the first proof of the matching kind
+fn find_proof<'a, T>(
+    proofs: &'a [MintProof],
+    kind: &str,
+    f: impl Fn(&'a MintProof) -> Option<T>,
+) -> Result<T, ContractError> {
+    proofs.iter().find_map(f).ok_or_else(|| ContractError::missing_proof(kind))
+}
+
+/// Assert that the owner can mint the badge under the specified rule, which is either the badge's
+/// rule or one of its sub-rules. Return the state changes to be applied upon minting.
+#[allow(clippy::too_many_arguments)]
+pub fn assert_can_mint(
+    deps: Deps,
+    block: &BlockInfo,
+    info: &MessageInfo,
+    id: u64,
+    badge: &Badge,
+    rule: &MintRule,
+    owner: &str,
+    message: &str,
+    proofs: &[MintProof],
+) -> Result<Vec<MintEffect>, ContractError> {
+    // the single-rule assertions read the rule from the badge, so in case this is a sub-rule, put
+    // it in a copy of the badge
+    let sub_badge = || Badge {
+        rule: rule.clone(),
+        ..badge.clone()
+    };
+
+    match rule {
+        MintRule::ByMinter(_) => {
+            assert_can_mint_by_minter(&sub_badge(), &info.sender)?;
+            // the minter must not bypass the recipient's consent by using the generic message
+            if badge.require_consent && info.sender != owner {
+                return Err(ContractError::ConsentRequired);
+            }
+            Ok(vec![])
+        },
+
+        MintRule::ByKey {
+            ..
+        } => {
+            let signature = find_proof(proofs, "signature", |proof| match proof {
+                MintProof::Signature(signature) => Some(signature),
+                _ => None,
+            })?;
+            assert_can_mint_by_key(deps.api, &sub_badge(), owner, message, signature)?;
+            Ok(vec![])
+        },
+
+        MintRule::ByKeys => {
+            let (pubkey, signature) = find_proof(proofs, "key_signature", |proof| match proof {
+                MintProof::KeySignature {
+                    pubkey,
+                    signature,
+                } => Some((pubkey, signature)),
+                _ => None,
+            })?;
+            assert_can_mint_by_keys(
+                deps,
+                block,
+                id,
+                &sub_badge(),
+                owner,
+                pubkey,
+                message,
+                signature,
+            )?;
+            Ok(vec![MintEffect::UseKey(pubkey.clone())])
+        },
+
+        // the owner must claim the badge themselves
+        MintRule::ByAllowlist => {
+            if info.sender != owner {
+                return Err(ContractError::NotSelfClaim);
+            }
+            assert_can_mint_by_allowlist(deps.storage, id, &sub_badge(), owner)?;
+            Ok(vec![])
+        },
+
+        MintRule::ByMerkleRoot {
+            ..
+        } => {
+            let (proof, amount) = find_proof(proofs, "merkle_proof", |proof| match proof {
+                MintProof::MerkleProof {
+                    proof,
+                    amount,
+                } => Some((proof, *amount)),
+                _ => None,
+            })?;
+            assert_can_mint_by_merkle_proof(deps.storage, id, &sub_badge(), owner, proof, amount)?;
+            Ok(vec![])
+        },
+
+        MintRule::ByPayment {
+            ..
+        } => {
+            let sub_badge = sub_badge();
+            let (price, recipient, paid) = assert_can_mint_by_payment(&sub_badge, info)?;
+            Ok(vec![MintEffect::Pay {
+                price: price.clone(),
+                recipient: recipient.to_string(),
+                paid,
+            }])
+        },
+
+        MintRule::ByPrerequisites {
+            ..
+        } => {
+            let nft_addr = NFT.load(deps.storage)?;
+            assert_can_mint_by_prerequisites(
+                deps.storage,
+                &deps.querier,
+                &nft_addr,
+                &sub_badge(),
+                owner,
+            )?;
+            Ok(vec![])
+        },
+
+        MintRule::ByOracle {
+            ..
+        } => {
+            // the oracle proof is optional
+            let proof = proofs.iter().find_map(|proof| match proof {
+                MintProof::OracleProof(proof) => Some(proof.clone()),
+                _ => None,
+            });
+            assert_can_mint_by_oracle(&deps.querier, id, &sub_badge(), owner, proof)?;
+            Ok(vec![])
+        },
+
+        MintRule::ByCollection {
+            ..
+        } => {
+            let token_id = find_proof(proofs, "collection_token", |proof| match proof {
+                MintProof::CollectionToken(token_id) => Some(token_id),
+                _ => None,
+            })?;
+            assert_can_mint_by_collection(deps, id, &sub_badge(), owner, token_id)?;
+            Ok(vec![MintEffect::UseToken(token_id.clone())])
+        },
+
+        MintRule::ByBalance {
+            ..
+        } => {
+            assert_can_mint_by_balance(&deps.querier, &sub_badge(), owner)?;
+            Ok(vec![])
+        },
+
+        // every sub-rule must be satisfied, and all of their effects apply
+        MintRule::All(rules) => {
+            let mut effects = vec![];
+            for rule in rules {
+                effects.extend(assert_can_mint(
+                    deps, block, info, id, badge, rule, owner, message, proofs,
+                )?);
+            }
+            Ok(effects)
+        },
+
+        // the first satisfied sub-rule takes effect. if none is satisfied, return the error of the
+        // last one, which is more helpful than a generic error
+        MintRule::Any(rules) => {
+            let mut last_err = ContractError::NoRuleSatisfied;
+            for rule in rules {
+                match assert_can_mint(deps, block, info, id, badge, rule, owner, message, proofs) {
+                    Ok(effects) => return Ok(effects),
+                    Err(err) => last_err = err,
+                }
+            }
+            Err(last_err)
+        },
+    }
+}
+
+/// The kinds of proofs that a rule consumes, including those consumed by its sub-rules. As only one
+/// sub-rule of an "any" rule takes effect, its sub-rules' kinds are only counted once.
+fn proof_kinds(rule: &MintRule) -> Vec<&'static str> {
+    match rule {
+        MintRule::ByKey {
+            ..
+        } => vec!["signature"],
+        MintRule::ByKeys => vec!["key_signature"],
+        MintRule::ByMerkleRoot {
+            ..
+        } => vec!["merkle_proof"],
+        MintRule::ByOracle {
+            ..
+        } => vec!["oracle_proof"],
+        MintRule::ByCollection {
+            ..
+        } => vec!["collection_token"],
+        MintRule::All(rules) => rules.iter().flat_map(proof_kinds).collect(),
+        MintRule::Any(rules) => {
+            let kinds = rules.iter().flat_map(proof_kinds).collect::<BTreeSet<_>>();
+            kinds.into_iter().collect()
+        },
+        _ => vec![],
+    }
+}
+
+/// Count the number of "by payment" rules in a rule, including its sub-rules
+fn count_payment_rules(rule: &MintRule) -> usize {
+    match rule {
+        MintRule::ByPayment {
+            ..
+        } => 1,
+        MintRule::All(rules) | MintRule::Any(rules) => rules.iter().map(count_payment_rules).sum(),
+        _ => 0,
+    }
+}
+
+/// Assert that the parameters of a mint rule are valid. This is checked when a badge is created.
+pub fn assert_valid_rule(deps: Deps, rule: &MintRule) -> Result<(), ContractError> {
+    match rule {
+        // the key must be a valid public key of the specified type
+        MintRule::ByKey {
+            key,
+            key_type,
+        } => {
+            let bytes = hex::decode(key)?;
+            assert_valid_pubkey(*key_type, &bytes)
+        },
+
+        // the root must be a valid sha256 hash
+        MintRule::ByMerkleRoot {
+            root,
+        } => {
+            let bytes = hex::decode(root)?;
+            if bytes.len() == SHA256_HASH_LEN {
+                Ok(())
+            } else {
+                Err(ContractError::InvalidMerkleRoot)
+            }
+        },
+
+        // the price must be non-zero, and the recipient must be a valid address
+        MintRule::ByPayment {
+            price,
+            recipient,
+        } => {
+            if price.amount.is_zero() {
+                return Err(ContractError::InvalidPrice);
+            }
+            deps.api.addr_validate(recipient)?;
+            Ok(())
+        },
+
+        // at least one prerequisite must be specified, and all prerequisites must exist
+        MintRule::ByPrerequisites {
+            all_of,
+            any_of,
+        } => {
+            if all_of.is_empty() && any_of.is_empty() {
+                return Err(ContractError::InvalidPrerequisites);
+            }
+            for id in all_of.iter().chain(any_of) {
+                if !BADGES.has(deps.storage, *id) {
+                    return Err(ContractError::InvalidPrerequisites);
+                }
+            }
+            Ok(())
+        },
+
+        // the oracle must be a valid address
+        MintRule::ByOracle {
+            contract,
+        } => {
+            deps.api.addr_validate(contract)?;
+            Ok(())
+        },
+
+        // the collection must be a valid address
+        MintRule::ByCollection {
+            collection,
+        } => {
+            deps.api.addr_validate(collection)?;
+            Ok(())
+        },
+
+        // the amount must be non-zero, and the denom must be valid
+        MintRule::ByBalance {
+            denom,
+            amount,
+        } => {
+            if amount.is_zero() {
+                return Err(ContractError::InvalidBalanceRequirement);
+            }
+            match denom {
+                Denom::Native(denom) if denom.is_empty() => {
+                    Err(ContractError::InvalidBalanceRequirement)
+                },
+                Denom::Native(_) => Ok(()),
+                Denom::Cw20(contract) => {
+                    deps.api.addr_validate(contract)?;
+                    Ok(())
+                },
+            }
+        },
+
+        // a composite rule must have at least one sub-rule, all of which must be valid. it may only
+        // contain one "by payment" rule, so that funds sent along are not counted twice.
+        //
+        // the proofs are looked up by their kinds, so the sub-rules of an "all" rule must not need
+        // more than one proof of the same kind, as they would all be checked against the same one
+        MintRule::All(rules) | MintRule::Any(rules) => {
+            if rules.is_empty() || count_payment_rules(rule) > 1 {
+                return Err(ContractError::InvalidCompositeRule);
+            }
+            if let MintRule::All(_) = rule {
+                let kinds = proof_kinds(rule);
+                if kinds.iter().collect::<BTreeSet<_>>().len() < kinds.len() {
+                    return Err(ContractError::InvalidCompositeRule);
+                }
+            }
+            rules.iter().try_for_each(|rule| assert_valid_rule(deps, rule))
+        },
+
+        MintRule::ByMinter(_) | MintRule::ByKeys | MintRule::ByAllowlist => Ok(()),
+    }
+}
+
+/// Assert that the per-user claim limit is valid for the badge's rule. A limit of zero would make
+/// the badge unclaimable. The signatures of the "by key" rule are not consumed upon minting, so if
+/// a user could claim more than once, anyone who has seen them could replay them.
+pub fn assert_valid_max_per_user(badge: &Badge) -> Result<(), ContractError> {
+    if badge.max_per_user == Some(0) {
+        return Err(ContractError::ZeroMaxPerUser);
+    }
+
+    if badge.rule.has_reusable_proofs() && badge.max_per_user != Some(1) {
+        return Err(ContractError::InvalidMaxPerUser);
+    }
+
+    Ok(())
+}
+
+/// Assert that a byte array is a valid public key of the specified type.
+pub fn assert_valid_pubkey(key_type: KeyType, bytes: &[u8]) -> Result<(), ContractError> {
+    match key_type {
+        KeyType::Secp256k1 => assert_valid_secp256k1_pubkey(bytes),
+        KeyType::Ed25519 => {
+            if bytes.len() == ED25519_PUBKEY_LEN {
+                Ok(())
+            } else {
+                Err(ContractError::InvalidPubkey)
+            }
+        },
+        // the wallet's address is derived from the compressed form of the pubkey
+        KeyType::Adr036 => {
+            if bytes.len() == ECDSA_COMPRESSED_PUBKEY_LEN {
+                assert_valid_secp256k1_pubkey(bytes)
+            } else {
+                Err(ContractError::InvalidPubkey)
+            }
+        },
+        KeyType::Ethereum => {
+            if bytes.len() == ETH_ADDRESS_LEN {
+                Ok(())
+            } else {
+                Err(ContractError::InvalidPubkey)
+            }
+        },
+    }
+}
+
 /// Assert that a byte array is a valid secp256k1 public key.
 ///
 /// Copied from cosmwasm-crypto:
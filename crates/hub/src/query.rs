@@ -1,12 +1,14 @@
-use cosmwasm_std::{Deps, Order, StdResult};
+use cosmwasm_std::{Deps, Env, Order, StdResult};
 use cw_storage_plus::Bound;
 
 use badges::hub::{
-    BadgeResponse, BadgesResponse, ConfigResponse, KeyResponse, KeysResponse, OwnerResponse,
-    OwnersResponse,
+    AllowlistResponse, AllowlistedResponse, BadgeResponse, BadgesResponse, BlockedBadgesResponse,
+    BlockedManagersResponse, BlockedResponse, ClaimMessageResponse, CollectionTokenResponse,
+    ConfigResponse, InstanceResponse, KeyResponse, KeysResponse, OfferResponse, OffersResponse,
+    OwnerResponse, OwnersResponse, PendingManagerResponse, VoucherResponse,
 };
 
-use crate::state::*;
+use crate::{helpers, state::*};
 
 pub const DEFAULT_LIMIT: u32 = 10;
 pub const MAX_LIMIT: u32 = 30;
@@ -51,12 +53,71 @@ pub fn badges(
     })
 }
 
-pub fn key(deps: Deps, id: u64, pubkey: impl Into<String>) -> KeyResponse {
-    let key = pubkey.into();
-    let whitelisted = KEYS.contains(deps.storage, (id, &key));
-    KeyResponse {
+pub fn pending_manager(deps: Deps, id: u64) -> StdResult<PendingManagerResponse> {
+    let pending_manager = PENDING_MANAGERS.may_load(deps.storage, id)?;
+    Ok(PendingManagerResponse {
+        id,
+        pending_manager: pending_manager.map(String::from),
+    })
+}
+
+pub fn key(deps: Deps, id: u64, pubkey: impl Into<String>) -> StdResult<KeyResponse> {
+    let key = helpers::normalize_key(&pubkey.into());
+    let info = KEYS.may_load(deps.storage, (id, &key))?;
+    Ok(KeyResponse {
         key,
-        whitelisted,
+        whitelisted: info.is_some(),
+        info,
+    })
+}
+
+pub fn allowlisted(deps: Deps, id: u64, user: impl Into<String>) -> AllowlistedResponse {
+    let user = user.into();
+    let allowlisted = ALLOWLIST.contains(deps.storage, (id, &user));
+    AllowlistedResponse {
+        user,
+        allowlisted,
+    }
+}
+
+pub fn allowlist(
+    deps: Deps,
+    id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllowlistResponse> {
+    let start = start_after.map(|user| Bound::ExclusiveRaw(user.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let users = ALLOWLIST
+        .prefix(id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AllowlistResponse {
+        users,
+    })
+}
+
+pub fn collection_token(
+    deps: Deps,
+    id: u64,
+    token_id: impl Into<String>,
+) -> CollectionTokenResponse {
+    let token_id = token_id.into();
+    let used = COLLECTION_TOKENS.contains(deps.storage, (id, &token_id));
+    CollectionTokenResponse {
+        token_id,
+        used,
+    }
+}
+
+pub fn voucher(deps: Deps, id: u64, nonce: u64) -> VoucherResponse {
+    let used = NONCES.contains(deps.storage, (id, nonce));
+    VoucherResponse {
+        nonce,
+        used,
     }
 }
 
@@ -71,8 +132,16 @@ pub fn keys(
 
     let keys = KEYS
         .prefix(id)
-        .keys(deps.storage, start, None, Order::Ascending)
+        .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
+        .map(|item| {
+            let (key, info) = item?;
+            Ok(KeyResponse {
+                key,
+                whitelisted: true,
+                info: Some(info),
+            })
+        })
         .collect::<StdResult<Vec<_>>>()?;
 
     Ok(KeysResponse {
@@ -80,15 +149,121 @@ pub fn keys(
     })
 }
 
+pub fn claim_message(
+    deps: Deps,
+    env: Env,
+    id: u64,
+    user: impl Into<String>,
+    deadline: Option<u64>,
+) -> StdResult<ClaimMessageResponse> {
+    let badge = BADGES.load(deps.storage, id)?;
+    Ok(ClaimMessageResponse {
+        version: badge.message_version,
+        message: helpers::claim_message(&env, id, &badge, &user.into(), deadline),
+    })
+}
+
+pub fn instance(deps: Deps, id: u64, serial: u64) -> StdResult<InstanceResponse> {
+    let tier = TIERS.may_load(deps.storage, (id, serial))?;
+    let revocation = REVOCATIONS.may_load(deps.storage, (id, serial))?;
+    Ok(InstanceResponse {
+        id,
+        serial,
+        tier,
+        revoked: revocation.is_some(),
+        revocation_reason: revocation.flatten(),
+    })
+}
+
+pub fn offer(deps: Deps, id: u64, user: impl Into<String>) -> OfferResponse {
+    let user = user.into();
+    let pending = OFFERS.contains(deps.storage, (id, &user));
+    OfferResponse {
+        user,
+        pending,
+    }
+}
+
+pub fn offers(
+    deps: Deps,
+    id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OffersResponse> {
+    let start = start_after.map(|user| Bound::ExclusiveRaw(user.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let users = OFFERS
+        .prefix(id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OffersResponse {
+        users,
+    })
+}
+
+pub fn blocked(deps: Deps, id: u64, user: impl Into<String>) -> StdResult<BlockedResponse> {
+    let user = user.into();
+    let badge = BADGES.load(deps.storage, id)?;
+    let blocked = helpers::assert_not_blocked(deps.storage, id, &badge, &user).is_err();
+    Ok(BlockedResponse {
+        user,
+        blocked,
+    })
+}
+
+pub fn blocked_badges(
+    deps: Deps,
+    user: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<BlockedBadgesResponse> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let ids = BLOCKED_BADGES
+        .prefix(&user)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BlockedBadgesResponse {
+        ids,
+    })
+}
+
+pub fn blocked_managers(
+    deps: Deps,
+    user: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BlockedManagersResponse> {
+    let start = start_after.map(|manager| Bound::ExclusiveRaw(manager.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let managers = BLOCKED_MANAGERS
+        .prefix(&user)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BlockedManagersResponse {
+        managers,
+    })
+}
+
 /// This function takes `impl Into<String>` instead of `String` so that i can type a few characters
 /// less in the unit tests =)
-pub fn owner(deps: Deps, id: u64, user: impl Into<String>) -> OwnerResponse {
+pub fn owner(deps: Deps, id: u64, user: impl Into<String>) -> StdResult<OwnerResponse> {
     let user = user.into();
-    let claimed = OWNERS.contains(deps.storage, (id, &user));
-    OwnerResponse {
+    let count = helpers::claim_count(deps.storage, id, &user)?;
+    Ok(OwnerResponse {
         user,
-        claimed,
-    }
+        claimed: count > 0,
+        count,
+    })
 }
 
 pub fn owners(
@@ -2,7 +2,7 @@ use cosmwasm_std::Addr;
 use cw_item_set::Set;
 use cw_storage_plus::{Item, Map};
 
-use badges::{Badge, FeeRate};
+use badges::{Badge, FeeRate, KeyInfo};
 
 /// Address of the developer
 pub const DEVELOPER: Item<Addr> = Item::new("owner");
@@ -19,11 +19,53 @@ pub const BADGE_COUNT: Item<u64> = Item::new("badge_count");
 /// Badges, indexed by ids
 pub const BADGES: Map<u64, Badge> = Map::new("badges");
 
-/// Pubkeys that are whitelisted to mint a badge
-pub const KEYS: Set<(u64, &str)> = Set::new("keys");
+/// Accounts that have been proposed as the new manager of a badge but have not accepted yet,
+/// indexed by badge ids
+pub const PENDING_MANAGERS: Map<u64, Addr> = Map::new("pending_managers");
+
+/// Pubkeys that are whitelisted to mint a badge, and their types, remaining uses, expiries, and
+/// tiers
+pub const KEYS: Map<(u64, &str), KeyInfo> = Map::new("keys");
+
+/// Addresses that are allowed to claim a badge under the "by allowlist" rule, indexed by
+/// {badge_id, user_addr}
+pub const ALLOWLIST: Set<(u64, &str)> = Set::new("allowlist");
+
+/// Tiers of the badge instances minted using tiered keys, indexed by {badge_id, serial}. Unlike
+/// keys, these are not purged once the badge becomes unavailable, as they describe instances that
+/// still exist.
+pub const TIERS: Map<(u64, u64), String> = Map::new("tiers");
+
+/// Instances of badges that have been revoked by their managers, and the reasons for revoking
+/// them, if any, indexed by {badge_id, serial}
+pub const REVOCATIONS: Map<(u64, u64), Option<String>> = Map::new("revocations");
+
+/// Tokens of external collections that have already been used to claim a badge under the
+/// "by collection" rule, indexed by {badge_id, token_id}
+pub const COLLECTION_TOKENS: Set<(u64, &str)> = Set::new("collection_tokens");
+
+/// Nonces of the minter-signed vouchers that have already been redeemed under the "by minter" rule,
+/// indexed by {badge_id, nonce}
+pub const NONCES: Set<(u64, u64)> = Set::new("nonces");
+
+/// Pending offers of badges that require consent, indexed by {badge_id, user_addr}
+pub const OFFERS: Set<(u64, &str)> = Set::new("offers");
+
+/// Badges that users have blocked from being minted to them, indexed by {user_addr, badge_id}
+pub const BLOCKED_BADGES: Set<(&str, u64)> = Set::new("blocked_badges");
+
+/// Managers whose badges users have blocked from being minted to them, indexed by
+/// {user_addr, manager_addr}
+pub const BLOCKED_MANAGERS: Set<(&str, &str)> = Set::new("blocked_managers");
 
 /// User addresses that have already claimed a badge. If a composite key {badge_id, user_addr}
 /// exists in the map, then this user has already claimed.
 ///
 /// Note that we don't verify the addresses here. The verifification is done by the NFT contract.
 pub const OWNERS: Set<(u64, &str)> = Set::new("claimed");
+
+/// The number of instances of a badge each user has claimed, indexed by {badge_id, user_addr}.
+///
+/// Users who claimed a badge before claim counts were introduced are in `OWNERS` but not in this
+/// map, and are considered to have claimed once.
+pub const CLAIM_COUNTS: Map<(u64, &str), u64> = Map::new("claim_counts");
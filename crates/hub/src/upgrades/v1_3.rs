@@ -0,0 +1,184 @@
+use cosmwasm_std::{Addr, DepsMut, Order, StdResult, Storage};
+use cw_item_set::Set;
+use cw_storage_plus::{Bound, Item, Map};
+use serde::{Deserialize, Serialize};
+use sg_metadata::Metadata;
+use sg_std::Response;
+
+use badges::{Badge, KeyInfo, KeyType, MessageVersion, MintRule};
+
+use crate::{
+    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    state::{BADGES, BADGE_COUNT, KEYS},
+};
+
+/// Badges as stored by v1.2, where the manager is mandatory, and the "by key" rule only has a
+/// secp256k1 key
+#[derive(Serialize, Deserialize)]
+struct LegacyBadge {
+    manager: Addr,
+    metadata: Metadata,
+    transferrable: bool,
+    rule: LegacyMintRule,
+    expiry: Option<u64>,
+    max_supply: Option<u64>,
+    current_supply: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+enum LegacyMintRule {
+    ByMinter(String),
+    ByKey(String),
+    ByKeys,
+}
+
+const LEGACY_BADGES: Map<u64, LegacyBadge> = Map::new("badges");
+
+/// Keys as stored by v1.2, as a set in the same namespace as the new map
+const LEGACY_KEYS: Set<(u64, &str)> = Set::new("keys");
+
+/// How far the migration has progressed, so that it can be carried out over multiple calls. Only
+/// exists while the migration is incomplete.
+const PROGRESS: Item<Progress> = Item::new("v1_3_progress");
+
+const DEFAULT_LIMIT: u32 = 100;
+
+#[derive(Serialize, Deserialize, Default)]
+struct Progress {
+    /// Id of the last badge that existed when the migration started. Badges created afterwards are
+    /// already in the new format, and must not be converted.
+    end_badge: u64,
+    /// Id of the last badge that has been converted
+    last_badge: Option<u64>,
+    /// Whether all badges have been converted
+    badges_done: bool,
+    /// The last key that has been converted
+    last_key: Option<(u64, String)>,
+    /// Whether all keys have been converted
+    keys_done: bool,
+}
+
+/// Converting every badge and key in one transaction may run out of gas, so at most `limit`
+/// entries are converted per call. The contract version is only bumped once all entries have been
+/// converted; until then, the migration can be invoked again to continue. Entries that have yet to
+/// be converted fail to deserialize, so the contract can't act on them in the meantime.
+pub fn migrate(deps: DepsMut, limit: Option<u32>) -> StdResult<Response> {
+    let mut progress = match PROGRESS.may_load(deps.storage)? {
+        Some(progress) => progress,
+        None => Progress {
+            end_badge: BADGE_COUNT.may_load(deps.storage)?.unwrap_or(0),
+            ..Default::default()
+        },
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+
+    // convert badges to the new format
+    let badges_migrated = update_badges(deps.storage, &mut progress, limit)?;
+
+    // store the type of each whitelisted key, which are all secp256k1
+    let keys_migrated = update_keys(deps.storage, &mut progress, limit - badges_migrated)?;
+
+    let complete = progress.badges_done && progress.keys_done;
+    if complete {
+        PROGRESS.remove(deps.storage);
+
+        // set the contract version to v1.3.0
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    } else {
+        PROGRESS.save(deps.storage, &progress)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "badges/hub/migrate")
+        .add_attribute("from_version", "1.2.0")
+        .add_attribute("to_version", "1.3.0")
+        .add_attribute("badges_migrated", badges_migrated.to_string())
+        .add_attribute("keys_migrated", keys_migrated.to_string())
+        .add_attribute("complete", complete.to_string()))
+}
+
+fn update_badges(
+    store: &mut dyn Storage,
+    progress: &mut Progress,
+    limit: usize,
+) -> StdResult<usize> {
+    if progress.badges_done {
+        return Ok(0);
+    }
+
+    let start = progress.last_badge.map(Bound::exclusive);
+    let end = Some(Bound::inclusive(progress.end_badge));
+    let legacy_badges = LEGACY_BADGES
+        .range(store, start, end, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (id, legacy_badge) in &legacy_badges {
+        let rule = match &legacy_badge.rule {
+            LegacyMintRule::ByMinter(minter) => MintRule::by_minter(minter),
+            LegacyMintRule::ByKey(key) => MintRule::by_key(key),
+            LegacyMintRule::ByKeys => MintRule::ByKeys,
+        };
+
+        let badge = Badge {
+            manager: Some(legacy_badge.manager.clone()),
+            metadata: legacy_badge.metadata.clone(),
+            transferrable: legacy_badge.transferrable,
+            rule,
+            start: None,
+            expiry: legacy_badge.expiry,
+            phases: vec![],
+            max_supply: legacy_badge.max_supply,
+            current_supply: legacy_badge.current_supply,
+            paused: false,
+            message_version: MessageVersion::Legacy,
+            max_per_user: Some(1),
+            require_consent: false,
+        };
+
+        BADGES.save(store, *id, &badge)?;
+    }
+
+    if let Some((id, _)) = legacy_badges.last() {
+        progress.last_badge = Some(*id);
+    }
+    progress.badges_done = legacy_badges.len() < limit;
+
+    Ok(legacy_badges.len())
+}
+
+fn update_keys(store: &mut dyn Storage, progress: &mut Progress, limit: usize) -> StdResult<usize> {
+    if !progress.badges_done || progress.keys_done {
+        return Ok(0);
+    }
+
+    // v1.2 stored the keys as a set, in the same namespace. the new map can't iterate them, as it
+    // would attempt to deserialize the empty values as key infos, so we read them using the set
+    let start = progress.last_key.as_ref().map(|(id, key)| Bound::exclusive((*id, key.as_str())));
+    let end = Some(Bound::exclusive((progress.end_badge + 1, "")));
+    let keys = LEGACY_KEYS
+        .items(store, start, end, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut count = 0;
+    for (id, key) in &keys {
+        // keys added to already converted badges while the migration is ongoing are in the new
+        // format, and must be left untouched
+        if KEYS.load(store, (*id, key)).is_ok() {
+            continue;
+        }
+
+        KEYS.save(store, (*id, key), &KeyInfo::new(KeyType::Secp256k1))?;
+        count += 1;
+    }
+
+    if let Some(last_key) = keys.last() {
+        progress.last_key = Some(last_key.clone());
+    }
+    progress.keys_done = keys.len() < limit;
+
+    Ok(count)
+}
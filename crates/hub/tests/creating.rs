@@ -1,12 +1,14 @@
+use std::collections::BTreeSet;
+
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
-use cosmwasm_std::{attr, Addr, Decimal, DepsMut, Empty, OwnedDeps};
+use cosmwasm_std::{attr, Addr, Decimal, DepsMut, Empty, OwnedDeps, StdResult};
 use sg_metadata::Metadata;
 use sg_std::Response;
 
 use badge_hub::error::ContractError;
 use badge_hub::state::*;
 use badge_hub::{execute, query};
-use badges::{Badge, MintRule, FeeRate};
+use badges::{Badge, KeyInfo, KeyType, MessageVersion, MintRule, FeeRate};
 
 mod utils;
 
@@ -32,6 +34,7 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
             &FeeRate {
                 metadata: Decimal::zero(),
                 key: Decimal::zero(),
+                mint: Decimal::zero(),
             },
         )
         .unwrap();
@@ -41,16 +44,22 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
 
 fn mock_badge() -> Badge {
     Badge {
-        manager: Addr::unchecked("larry"),
+        manager: Some(Addr::unchecked("larry")),
         metadata: Metadata {
             name: Some("first-badge".to_string()),
             ..Default::default()
         },
         transferrable: true,
         rule: MintRule::ByKeys,
+        start: None,
         expiry: Some(12345),
+        phases: vec![],
         max_supply: Some(100),
         current_supply: 0,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
     }
 }
 
@@ -80,6 +89,35 @@ fn creating_unavailable_badges() {
         assert_eq!(err, ContractError::Expired);
     }
 
+    // can create a badge whose minting has not started yet
+    {
+        let mut badge = mock_badge();
+        badge.start = Some(11111);
+
+        execute::create_badge(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            badge,
+        )
+        .unwrap();
+    }
+
+    // cannot create a badge whose minting schedule doesn't make sense
+    {
+        let mut badge = mock_badge();
+        badge.start = Some(99999);
+
+        let err = execute::create_badge(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            badge,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSchedule);
+    }
+
     // cannot create a badge that has zero max supply
     {
         let mut badge = mock_badge();
@@ -96,6 +134,54 @@ fn creating_unavailable_badges() {
     }
 }
 
+#[test]
+fn rejecting_replayable_claim_limits() {
+    let mut deps = setup_test();
+
+    let create = |deps: DepsMut, rule: MintRule, max_per_user: Option<u64>| {
+        execute::create_badge(
+            deps,
+            utils::mock_env_at_timestamp(10000),
+            mock_info("creator", &[]),
+            Badge {
+                rule,
+                max_per_user,
+                ..mock_badge()
+            },
+        )
+    };
+
+    let by_key = MintRule::ByKey {
+        key: KEY_1.to_string(),
+        key_type: KeyType::Secp256k1,
+    };
+    let by_merkle_root = MintRule::ByMerkleRoot {
+        root: hex::encode([0u8; 32]),
+    };
+
+    // the same signature could be replayed if users can claim more than once
+    for max_per_user in [Some(2), None] {
+        let err = create(deps.as_mut(), by_key.clone(), max_per_user).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxPerUser);
+
+        let rule = MintRule::Any(vec![MintRule::ByKeys, by_key.clone()]);
+        let err = create(deps.as_mut(), rule, max_per_user).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxPerUser);
+
+        // keys of the "by keys" rule are consumed upon minting, so they can't be replayed
+        create(deps.as_mut(), MintRule::ByKeys, max_per_user).unwrap();
+
+        // merkle proofs can only be used as many times as the amount in the leaf
+        create(deps.as_mut(), by_merkle_root.clone(), max_per_user).unwrap();
+    }
+
+    create(deps.as_mut(), by_key, Some(1)).unwrap();
+
+    // a limit of zero would make the badge unclaimable
+    let err = create(deps.as_mut(), MintRule::ByKeys, Some(0)).unwrap_err();
+    assert_eq!(err, ContractError::ZeroMaxPerUser);
+}
+
 #[test]
 fn creating_badge() {
     let mut deps = setup_test();
@@ -103,16 +189,22 @@ fn creating_badge() {
     // create the first badge
     {
         let badge = Badge {
-            manager: Addr::unchecked("larry"),
+            manager: Some(Addr::unchecked("larry")),
             metadata: Metadata {
                 name: Some("first-badge".to_string()),
                 ..Default::default()
             },
             transferrable: true,
             rule: MintRule::ByMinter("larry".to_string()),
+            start: None,
             expiry: Some(12345),
+            phases: vec![],
             max_supply: Some(100),
             current_supply: 0,
+            paused: false,
+            message_version: MessageVersion::Legacy,
+            max_per_user: Some(1),
+            require_consent: false,
         };
 
         let res = create_badge(deps.as_mut(), &badge);
@@ -136,16 +228,22 @@ fn creating_badge() {
     // create the second badge
     {
         let badge = Badge {
-            manager: Addr::unchecked("jake"),
+            manager: Some(Addr::unchecked("jake")),
             metadata: Metadata {
                 name: Some("second-badge".to_string()),
                 ..Default::default()
             },
             transferrable: false,
             rule: MintRule::ByKeys,
+            start: None,
             expiry: None,
+            phases: vec![],
             max_supply: None,
             current_supply: 0,
+            paused: false,
+            message_version: MessageVersion::Legacy,
+            max_per_user: Some(1),
+            require_consent: false,
         };
 
         let res = create_badge(deps.as_mut(), &badge);
@@ -190,7 +288,7 @@ fn editing_badge() {
     {
         let res = execute::edit_badge(
             deps.as_mut(),
-            mock_info(badge.manager.as_str(), &[]),
+            mock_info("larry", &[]),
             1,
             Metadata::default(),
         )
@@ -211,150 +309,1062 @@ fn editing_badge() {
 }
 
 #[test]
-fn adding_keys() {
+fn editing_badge_config() {
     let mut deps = setup_test();
 
-    // badge 1 has mint rule "by keys"
-    let mut badge = mock_badge();
+    let badge = mock_badge();
     create_badge(deps.as_mut(), &badge);
 
-    // badge 2 has mint rule "by minter"
-    badge.rule = MintRule::ByMinter("pumpkin".to_string());
+    fn edit_config(
+        deps: DepsMut,
+        timestamp: u64,
+        sender: &str,
+        expiry: Option<u64>,
+        max_supply: Option<u64>,
+        transferrable: Option<bool>,
+    ) -> Result<Response, ContractError> {
+        execute::edit_badge_config(
+            deps,
+            utils::mock_env_at_timestamp(timestamp),
+            mock_info(sender, &[]),
+            1,
+            expiry,
+            max_supply,
+            transferrable,
+        )
+    }
+
+    // non-manager cannot edit
+    {
+        let err = edit_config(deps.as_mut(), 10000, "jake", Some(23456), None, None).unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot shorten the deadline
+    {
+        let err = edit_config(deps.as_mut(), 10000, "larry", Some(12344), None, None).unwrap_err();
+        assert_eq!(err, ContractError::InvalidExpiry);
+    }
+
+    // cannot extend the deadline once it has been reached
+    {
+        let err = edit_config(deps.as_mut(), 99999, "larry", Some(123456), None, None).unwrap_err();
+        assert_eq!(err, ContractError::Expired);
+    }
+
+    // cannot raise the max supply
+    {
+        let err = edit_config(deps.as_mut(), 10000, "larry", None, Some(101), None).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxSupply);
+    }
+
+    // cannot lower the max supply below the current supply
+    {
+        BADGES
+            .update(deps.as_mut().storage, 1, |opt| -> StdResult<_> {
+                let mut badge = opt.unwrap();
+                badge.current_supply = 50;
+                Ok(badge)
+            })
+            .unwrap();
+
+        let err = edit_config(deps.as_mut(), 10000, "larry", None, Some(49), None).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxSupply);
+    }
+
+    // manager properly edits
+    {
+        let res = edit_config(deps.as_mut(), 10000, "larry", Some(23456), Some(50), Some(false))
+            .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/edit_badge_config"),
+                attr("id", "1"),
+                attr("expiry", "23456"),
+                attr("max_supply", "50"),
+                attr("transferrable", "false"),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.expiry, Some(23456));
+        assert_eq!(b.max_supply, Some(50));
+        assert!(!b.transferrable);
+    }
+
+    // cannot make a non-transferrable badge transferrable again
+    {
+        let err = edit_config(deps.as_mut(), 10000, "larry", None, None, Some(true)).unwrap_err();
+        assert_eq!(err, ContractError::CannotMakeTransferrable);
+    }
+}
+
+#[test]
+fn pausing_badge() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
     create_badge(deps.as_mut(), &badge);
 
-    // non-manager cannot add key
+    // non-manager cannot pause
     {
-        let err = execute::add_keys(
-            deps.as_mut(),
-            utils::mock_env_at_timestamp(10000),
-            mock_info("jake", &[]),
-            1,
-            utils::btreeset(&[KEY_1]),
-        )
-        .unwrap_err();
+        let err = execute::set_paused(deps.as_mut(), mock_info("jake", &[]), 1, true).unwrap_err();
         assert_eq!(err, ContractError::NotManager);
     }
 
-    // cannot add key if the badge is not of "by keys" mint rule
+    // manager pauses
     {
-        let err = execute::add_keys(
-            deps.as_mut(),
-            utils::mock_env_at_timestamp(10000),
-            mock_info("larry", &[]),
-            2,
-            utils::btreeset(&[KEY_1]),
-        )
-        .unwrap_err();
-        assert_eq!(err, ContractError::wrong_mint_rule("by_keys", &badge.rule));
+        let res = execute::set_paused(deps.as_mut(), mock_info("larry", &[]), 1, true).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/set_paused"),
+                attr("id", "1"),
+                attr("paused", "true"),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert!(b.paused);
     }
 
-    // cannot add key if the badge is no longer available
+    // manager resumes
     {
-        let err = execute::add_keys(
+        execute::set_paused(deps.as_mut(), mock_info("larry", &[]), 1, false).unwrap();
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert!(!b.paused);
+    }
+}
+
+#[test]
+fn transferring_manager() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+
+    // non-manager cannot propose a new manager
+    {
+        let err = execute::transfer_manager(
             deps.as_mut(),
-            utils::mock_env_at_timestamp(99999),
-            mock_info("larry", &[]),
+            mock_info("jake", &[]),
             1,
-            utils::btreeset(&[KEY_1]),
+            "jake".to_string(),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::Expired);
+        assert_eq!(err, ContractError::NotManager);
     }
 
-    // cannot add invalid hex-encoded strings
+    // manager proposes a new manager
     {
-        let err = execute::add_keys(
+        let res = execute::transfer_manager(
             deps.as_mut(),
-            utils::mock_env_at_timestamp(10000),
             mock_info("larry", &[]),
             1,
-            utils::btreeset(&["ngmi"]),
+            "jake".to_string(),
         )
-        .unwrap_err();
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
         assert_eq!(
-            err,
-            ContractError::FromHex(hex::FromHexError::InvalidHexCharacter {
-                c: 'n',
-                index: 0
-            }),
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/transfer_manager"),
+                attr("id", "1"),
+                attr("pending_manager", "jake"),
+            ],
+        );
+
+        let res = query::pending_manager(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.pending_manager, Some("jake".to_string()));
+
+        // the manager doesn't change until the handover is accepted
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.manager, Some("larry".to_string()));
+    }
+
+    // an account other than the pending manager cannot accept
+    {
+        let err = execute::accept_manager(deps.as_mut(), mock_info("pumpkin", &[]), 1).unwrap_err();
+        assert_eq!(err, ContractError::NotPendingManager);
+    }
+
+    // pending manager accepts
+    {
+        let res = execute::accept_manager(deps.as_mut(), mock_info("jake", &[]), 1).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/accept_manager"),
+                attr("id", "1"),
+                attr("previous_manager", "larry"),
+                attr("new_manager", "jake"),
+            ],
         );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.manager, Some("jake".to_string()));
+
+        let res = query::pending_manager(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.pending_manager, None);
     }
 
-    // manager properly adds keys
+    // the previous manager can no longer edit
     {
-        let res = execute::add_keys(
+        let err = execute::edit_badge(
             deps.as_mut(),
-            utils::mock_env_at_timestamp(10000),
             mock_info("larry", &[]),
             1,
-            utils::btreeset(&[KEY_1, KEY_2]),
+            Metadata::default(),
         )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot accept twice
+    {
+        let err = execute::accept_manager(deps.as_mut(), mock_info("jake", &[]), 1).unwrap_err();
+        assert_eq!(err, ContractError::NotPendingManager);
+    }
+}
+
+#[test]
+fn renouncing_manager() {
+    let mut deps = setup_test();
+
+    let badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+
+    // propose a handover, which should be discarded once the manager renounces
+    execute::transfer_manager(deps.as_mut(), mock_info("larry", &[]), 1, "jake".to_string())
         .unwrap();
+
+    // non-manager cannot renounce
+    {
+        let err = execute::renounce_manager(deps.as_mut(), mock_info("jake", &[]), 1).unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager renounces
+    {
+        let res = execute::renounce_manager(deps.as_mut(), mock_info("larry", &[]), 1).unwrap();
         assert_eq!(res.messages, vec![]);
         assert_eq!(
             res.attributes,
             vec![
-                attr("action", "badges/hub/add_keys"),
+                attr("action", "badges/hub/renounce_manager"),
                 attr("id", "1"),
-                attr("fee", "[]"),
-                attr("keys_added", "2"),
             ],
         );
 
-        let res = query::keys(deps.as_ref(), 1, None, None).unwrap();
-        assert_eq!(res.keys, vec![KEY_1.to_string(), KEY_2.to_string()]);
-    }
-}
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.manager, None);
 
-#[test]
-fn rejecting_invalid_keys() {
-    let mut deps = setup_test();
+        let res = query::pending_manager(deps.as_ref(), 1).unwrap();
+        assert_eq!(res.pending_manager, None);
+    }
 
-    // cannot create a new badge with invalid key
+    // the pending handover can no longer be accepted
     {
-        let err = execute::create_badge(
-            deps.as_mut(),
-            mock_env(),
-            mock_info("larry", &[]),
-            Badge {
-                manager: Addr::unchecked("larry"),
-                metadata: Metadata::default(),
-                transferrable: false,
-                rule: MintRule::ByKey(INVALID_KEY.into()),
-                expiry: None,
-                max_supply: None,
-                current_supply: 0,
-            },
-        )
-        .unwrap_err();
-        assert_eq!(err, ContractError::InvalidPubkey);
+        let err = execute::accept_manager(deps.as_mut(), mock_info("jake", &[]), 1).unwrap_err();
+        assert_eq!(err, ContractError::NotPendingManager);
     }
 
-    // cannot add invalid keys to an existing badge
+    // no one can edit the badge or add keys anymore
     {
-        // first, properly create a badge with the "by keys" minting rule
-        execute::create_badge(
+        let err = execute::edit_badge(
             deps.as_mut(),
-            mock_env(),
             mock_info("larry", &[]),
-            Badge {
-                manager: Addr::unchecked("larry"),
-                metadata: Metadata::default(),
-                transferrable: false,
-                rule: MintRule::ByKeys,
-                expiry: None,
-                max_supply: None,
-                current_supply: 0,
-            },
+            1,
+            Metadata::default(),
         )
-        .unwrap();
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
 
         let err = execute::add_keys(
             deps.as_mut(),
-            mock_env(),
+            utils::mock_env_at_timestamp(10000),
             mock_info("larry", &[]),
             1,
-            utils::btreeset(&[KEY_1, KEY_2, INVALID_KEY]),
+            utils::btreeset(&[KEY_1]),
+            KeyInfo::new(KeyType::Secp256k1),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+}
+
+#[test]
+fn rotating_key() {
+    let mut deps = setup_test();
+
+    // badge 1 has mint rule "by key"
+    let mut badge = mock_badge();
+    badge.rule = MintRule::by_key(KEY_1);
+    create_badge(deps.as_mut(), &badge);
+
+    // badge 2 has mint rule "by keys"
+    create_badge(deps.as_mut(), &mock_badge());
+
+    // larry has already claimed badge 1
+    OWNERS.insert(deps.as_mut().storage, (1, "larry")).unwrap();
+
+    // non-manager cannot rotate key
+    {
+        let err = execute::rotate_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            1,
+            KEY_2.to_string(),
+            KeyType::Secp256k1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot rotate key if the badge is not of "by key" mint rule
+    {
+        let err = execute::rotate_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            KEY_2.to_string(),
+            KeyType::Secp256k1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_key", &MintRule::ByKeys));
+    }
+
+    // cannot rotate to an invalid key
+    {
+        let err = execute::rotate_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            INVALID_KEY.to_string(),
+            KeyType::Secp256k1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPubkey);
+    }
+
+    // manager properly rotates key
+    {
+        let res = execute::rotate_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            KEY_2.to_string(),
+            KeyType::Secp256k1,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/rotate_key"),
+                attr("id", "1"),
+                attr("fee", "[]"),
+                attr("key", KEY_2),
+            ],
+        );
+
+        let b = query::badge(deps.as_ref(), 1).unwrap();
+        assert_eq!(b.rule, MintRule::by_key(KEY_2));
+
+        // existing claims are kept
+        let res = query::owner(deps.as_ref(), 1, "larry").unwrap();
+        assert!(res.claimed);
+    }
+}
+
+#[test]
+fn adding_keys() {
+    let mut deps = setup_test();
+
+    // badge 1 has mint rule "by keys"
+    let mut badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+
+    // badge 2 has mint rule "by minter"
+    badge.rule = MintRule::ByMinter("pumpkin".to_string());
+    create_badge(deps.as_mut(), &badge);
+
+    // non-manager cannot add key
+    {
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            1,
+            utils::btreeset(&[KEY_1]),
+            KeyInfo::new(KeyType::Secp256k1),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot add key if the badge is not of "by keys" mint rule
+    {
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            utils::btreeset(&[KEY_1]),
+            KeyInfo::new(KeyType::Secp256k1),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_keys", &badge.rule));
+    }
+
+    // cannot add key if the badge is no longer available
+    {
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[KEY_1]),
+            KeyInfo::new(KeyType::Secp256k1),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Expired);
+    }
+
+    // cannot add keys that can't be used even once
+    {
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[KEY_1]),
+            KeyInfo {
+                uses: 0,
+                ..KeyInfo::new(KeyType::Secp256k1)
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidKeyUses);
+    }
+
+    // cannot add keys with an overly long tier
+    {
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[KEY_1]),
+            KeyInfo {
+                tier: Some("a".repeat(33)),
+                ..KeyInfo::new(KeyType::Secp256k1)
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TierTooLong {
+                max: 32,
+            },
+        );
+    }
+
+    // can add keys before minting starts
+    {
+        BADGES
+            .update(deps.as_mut().storage, 1, |opt| -> StdResult<_> {
+                let mut badge = opt.unwrap();
+                badge.start = Some(11111);
+                Ok(badge)
+            })
+            .unwrap();
+
+        execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[KEY_2]),
+            KeyInfo::new(KeyType::Secp256k1),
+        )
+        .unwrap();
+    }
+
+    // cannot add invalid hex-encoded strings
+    {
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&["ngmi"]),
+            KeyInfo::new(KeyType::Secp256k1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FromHex(hex::FromHexError::InvalidHexCharacter {
+                c: 'n',
+                index: 0
+            }),
+        );
+    }
+
+    // manager properly adds keys
+    {
+        let res = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[KEY_1]),
+            KeyInfo::new(KeyType::Secp256k1),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/add_keys"),
+                attr("id", "1"),
+                attr("fee", "[]"),
+                attr("keys_added", "1"),
+            ],
+        );
+
+        let res = query::keys(deps.as_ref(), 1, None, None).unwrap();
+        let keys = res.keys.into_iter().map(|res| res.key).collect::<Vec<_>>();
+        assert_eq!(keys, vec![KEY_1.to_string(), KEY_2.to_string()]);
+    }
+}
+
+#[test]
+fn revoking_keys() {
+    let mut deps = setup_test();
+
+    // badge 1 has mint rule "by keys"
+    let mut badge = mock_badge();
+    create_badge(deps.as_mut(), &badge);
+
+    // badge 2 has mint rule "by minter"
+    badge.rule = MintRule::ByMinter("pumpkin".to_string());
+    create_badge(deps.as_mut(), &badge);
+
+    execute::add_keys(
+        deps.as_mut(),
+        utils::mock_env_at_timestamp(10000),
+        mock_info("larry", &[]),
+        1,
+        utils::btreeset(&[KEY_1, KEY_2]),
+        KeyInfo::new(KeyType::Secp256k1),
+    )
+    .unwrap();
+
+    // non-manager cannot revoke keys
+    {
+        let err = execute::revoke_keys(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            1,
+            utils::btreeset(&[KEY_1]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+
+        let err = execute::revoke_all_keys(deps.as_mut(), mock_info("jake", &[]), 1, None)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot revoke keys if the badge is not of "by keys" mint rule
+    {
+        let err = execute::revoke_keys(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            2,
+            utils::btreeset(&[KEY_1]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_keys", &badge.rule));
+    }
+
+    // manager revokes specific keys; keys that are not whitelisted are ignored
+    {
+        let res = execute::revoke_keys(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[KEY_1, INVALID_KEY]),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/revoke_keys"),
+                attr("id", "1"),
+                attr("keys_revoked", "1"),
+            ],
+        );
+
+        let res = query::keys(deps.as_ref(), 1, None, None).unwrap();
+        let keys = res.keys.into_iter().map(|res| res.key).collect::<Vec<_>>();
+        assert_eq!(keys, vec![KEY_2.to_string()]);
+    }
+
+    // manager revokes all keys
+    {
+        let res = execute::revoke_all_keys(deps.as_mut(), mock_info("larry", &[]), 1, None)
+            .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/revoke_all_keys"),
+                attr("id", "1"),
+                attr("keys_revoked", "1"),
+            ],
+        );
+
+        let res = query::keys(deps.as_ref(), 1, None, None).unwrap();
+        assert!(res.keys.is_empty());
+    }
+}
+
+#[test]
+fn managing_allowlist() {
+    let mut deps = setup_test();
+
+    // badge 1 has mint rule "by allowlist"
+    let mut badge = mock_badge();
+    badge.rule = MintRule::ByAllowlist;
+    create_badge(deps.as_mut(), &badge);
+
+    // badge 2 has mint rule "by keys"
+    create_badge(deps.as_mut(), &mock_badge());
+
+    let add = |deps: DepsMut, sender: &str, id: u64, users: &[&str]| {
+        execute::add_allowlist(
+            deps,
+            utils::mock_env_at_timestamp(10000),
+            mock_info(sender, &[]),
+            id,
+            utils::btreeset(users),
+        )
+    };
+
+    // non-manager cannot add users
+    {
+        let err = add(deps.as_mut(), "jake", 1, &["jake"]).unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot add users if the badge is not of "by allowlist" mint rule
+    {
+        let err = add(deps.as_mut(), "larry", 2, &["jake"]).unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_allowlist", &MintRule::ByKeys));
+    }
+
+    // manager properly adds users
+    {
+        let res = add(deps.as_mut(), "larry", 1, &["jake", "pumpkin"]).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/add_allowlist"),
+                attr("id", "1"),
+                attr("fee", "[]"),
+                attr("users_added", "2"),
+            ],
+        );
+
+        let res = query::allowlisted(deps.as_ref(), 1, "jake");
+        assert!(res.allowlisted);
+
+        let res = query::allowlist(deps.as_ref(), 1, None, None).unwrap();
+        assert_eq!(res.users, vec!["jake".to_string(), "pumpkin".to_string()]);
+    }
+
+    // cannot add a user twice
+    {
+        let err = add(deps.as_mut(), "larry", 1, &["jake"]).unwrap_err();
+        assert_eq!(err, ContractError::already_allowlisted(1, "jake"));
+    }
+
+    // non-manager cannot remove users
+    {
+        let err = execute::remove_allowlist(
+            deps.as_mut(),
+            mock_info("jake", &[]),
+            1,
+            utils::btreeset(&["jake"]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // manager properly removes users; users not on the allowlist are ignored
+    {
+        let res = execute::remove_allowlist(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&["doge", "jake"]),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/remove_allowlist"),
+                attr("id", "1"),
+                attr("users_removed", "1"),
+            ],
+        );
+
+        let res = query::allowlist(deps.as_ref(), 1, None, None).unwrap();
+        assert_eq!(res.users, vec!["pumpkin".to_string()]);
+    }
+
+    // cannot add users once the badge is no longer available
+    {
+        let err = execute::add_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&["jake"]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Expired);
+    }
+}
+
+#[test]
+fn revoking_instances() {
+    let mut deps = setup_test();
+
+    create_badge(deps.as_mut(), &mock_badge());
+
+    // two instances have been minted
+    BADGES
+        .update(deps.as_mut().storage, 1, |badge| {
+            let mut badge = badge.unwrap();
+            badge.current_supply = 2;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    let revoke = |deps: DepsMut, sender: &str, serial: u64, reason: Option<&str>| {
+        execute::revoke_instance(
+            deps,
+            mock_info(sender, &[]),
+            1,
+            serial,
+            reason.map(String::from),
+        )
+    };
+
+    // non-manager cannot revoke
+    {
+        let err = revoke(deps.as_mut(), "jake", 1, None).unwrap_err();
+        assert_eq!(err, ContractError::NotManager);
+    }
+
+    // cannot revoke an instance that hasn't been minted
+    {
+        let err = revoke(deps.as_mut(), "larry", 0, None).unwrap_err();
+        assert_eq!(err, ContractError::instance_not_found(1, 0));
+
+        let err = revoke(deps.as_mut(), "larry", 3, None).unwrap_err();
+        assert_eq!(err, ContractError::instance_not_found(1, 3));
+    }
+
+    // manager properly revokes an instance
+    {
+        let res = revoke(deps.as_mut(), "larry", 2, Some("cheating")).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/revoke_instance"),
+                attr("id", "1"),
+                attr("serial", "2"),
+                attr("fee", "[]"),
+            ],
+        );
+
+        let res = query::instance(deps.as_ref(), 1, 2).unwrap();
+        assert!(res.revoked);
+        assert_eq!(res.revocation_reason, Some("cheating".to_string()));
+
+        // the other instance is unaffected
+        let res = query::instance(deps.as_ref(), 1, 1).unwrap();
+        assert!(!res.revoked);
+
+        // the supply is unchanged
+        let badge = BADGES.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(badge.current_supply, 2);
+    }
+
+    // cannot revoke an instance twice
+    {
+        let err = revoke(deps.as_mut(), "larry", 2, None).unwrap_err();
+        assert_eq!(err, ContractError::already_revoked(1, 2));
+    }
+
+    // a reason is optional
+    {
+        revoke(deps.as_mut(), "larry", 1, None).unwrap();
+
+        let res = query::instance(deps.as_ref(), 1, 1).unwrap();
+        assert!(res.revoked);
+        assert_eq!(res.revocation_reason, None);
+    }
+}
+
+#[test]
+fn rejecting_invalid_keys() {
+    let mut deps = setup_test();
+
+    // cannot create a new badge with invalid key
+    {
+        let err = execute::create_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: false,
+                rule: MintRule::by_key(INVALID_KEY),
+                start: None,
+                expiry: None,
+                phases: vec![],
+                max_supply: None,
+                current_supply: 0,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPubkey);
+    }
+
+    // cannot create a new badge with an invalid merkle root
+    {
+        let err = execute::create_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: false,
+                rule: MintRule::ByMerkleRoot {
+                    root: "deadbeef".to_string(),
+                },
+                start: None,
+                expiry: None,
+                phases: vec![],
+                max_supply: None,
+                current_supply: 0,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidMerkleRoot);
+    }
+
+    // cannot create a new badge with prerequisites that don't exist
+    {
+        let err = execute::create_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: false,
+                rule: MintRule::ByPrerequisites {
+                    all_of: BTreeSet::from([69]),
+                    any_of: BTreeSet::new(),
+                },
+                start: None,
+                expiry: None,
+                phases: vec![],
+                max_supply: None,
+                current_supply: 0,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPrerequisites);
+    }
+
+    // cannot add invalid keys to an existing badge
+    {
+        // first, properly create a badge with the "by keys" minting rule
+        execute::create_badge(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: false,
+                rule: MintRule::ByKeys,
+                start: None,
+                expiry: None,
+                phases: vec![],
+                max_supply: None,
+                current_supply: 0,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+        let err = execute::add_keys(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("larry", &[]),
+            1,
+            utils::btreeset(&[KEY_1, KEY_2, INVALID_KEY]),
+            KeyInfo::new(KeyType::Secp256k1),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPubkey);
+    }
+}
+
+#[test]
+fn defaulting_to_v2_messages() {
+    let msg: badges::hub::ExecuteMsg = cosmwasm_std::from_slice(
+        br#"{
+            "create_badge": {
+                "manager": "larry",
+                "metadata": {},
+                "transferrable": true,
+                "rule": { "by_minter": "larry" },
+                "start": null,
+                "expiry": null,
+                "max_supply": null
+            }
+        }"#,
+    )
+    .unwrap();
+
+    match msg {
+        badges::hub::ExecuteMsg::CreateBadge {
+            message_version,
+            max_per_user,
+            ..
+        } => {
+            assert_eq!(message_version, MessageVersion::V2);
+            assert_eq!(max_per_user, Some(1));
+        },
+        _ => panic!("expected a create_badge message"),
+    }
+}
+
+#[test]
+fn normalizing_ethereum_keys() {
+    let mut deps = setup_test();
+
+    let address = utils::eth_address(&utils::mock_privkey());
+    let checksummed = format!("0x{}", address.to_uppercase());
+
+    // the key of the "by key" rule is stored without the prefix, in lowercase
+    {
+        let rule = MintRule::ByKey {
+            key: checksummed.clone(),
+            key_type: KeyType::Ethereum,
+        };
+        create_badge(deps.as_mut(), &Badge {
+            rule,
+            ..mock_badge()
+        });
+
+        let badge = BADGES.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(
+            badge.rule,
+            MintRule::ByKey {
+                key: address.clone(),
+                key_type: KeyType::Ethereum,
+            },
+        );
+    }
+
+    // whitelisted addresses are stored the same way, and can be looked up in either form
+    {
+        create_badge(deps.as_mut(), &mock_badge());
+
+        execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            utils::btreeset(&[&checksummed]),
+            KeyInfo::new(KeyType::Ethereum),
+        )
+        .unwrap();
+
+        assert!(KEYS.has(deps.as_ref().storage, (2, &address)));
+        assert!(query::key(deps.as_ref(), 2, &checksummed).unwrap().whitelisted);
+
+        // the same address can't be added again in another form
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            utils::btreeset(&[&address]),
+            KeyInfo::new(KeyType::Ethereum),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::key_exists(2, &address));
+
+        // and can be revoked in either form
+        execute::revoke_keys(
+            deps.as_mut(),
+            mock_info("larry", &[]),
+            2,
+            utils::btreeset(&[&checksummed]),
+        )
+        .unwrap();
+        assert!(!KEYS.has(deps.as_ref().storage, (2, &address)));
+    }
+
+    // an address of the wrong length is rejected
+    {
+        let err = execute::add_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            2,
+            utils::btreeset(&[&address[..38]]),
+            KeyInfo::new(KeyType::Ethereum),
         )
         .unwrap_err();
         assert_eq!(err, ContractError::InvalidPubkey);
@@ -1,12 +1,24 @@
-use cosmwasm_std::testing::{mock_dependencies};
-use cosmwasm_std::{attr, Addr, Decimal};
+use std::collections::BTreeSet;
 
-use badges::FeeRate;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{attr, Addr, Decimal, DepsMut};
+use cw_item_set::Set;
+use cw_storage_plus::Map;
+use serde::{Deserialize, Serialize};
+use sg_metadata::Metadata;
+use sg_std::Response;
 
+use badges::hub::MigrateMsg;
+use badges::{Badge, FeeRate, KeyInfo, KeyType, MessageVersion, MintRule};
+
+use badge_hub::contract::{self, CONTRACT_NAME, CONTRACT_VERSION};
 use badge_hub::error::ContractError;
-use badge_hub::state::{BADGE_COUNT, NFT, DEVELOPER};
+use badge_hub::state::{BADGES, BADGE_COUNT, DEVELOPER, FEE_RATE, KEYS, NFT};
 use badge_hub::execute;
 
+// a valid secp256k1 public key for testing purpose
+const KEY: &str = "026f476708bd8fcc8a58bae717ee6922cdefd7917492dbc1a4c2f96d22ba30e470";
+
 #[test]
 fn instantiating() {
     let mut deps = mock_dependencies();
@@ -17,6 +29,7 @@ fn instantiating() {
         FeeRate {
             metadata: Decimal::from_ratio(10u128, 1u128),
             key: Decimal::from_ratio(2u128, 1u128),
+            mint: Decimal::zero(),
         },
     )
     .unwrap();
@@ -27,6 +40,34 @@ fn instantiating() {
     assert_eq!(badge_count, 0);
 }
 
+#[test]
+fn rejecting_invalid_fee_rates() {
+    let mut deps = mock_dependencies();
+
+    let fee_rate = FeeRate {
+        metadata: Decimal::zero(),
+        key: Decimal::zero(),
+        mint: Decimal::from_ratio(101u128, 100u128),
+    };
+
+    let err = execute::init(deps.as_mut(), Addr::unchecked("larry"), fee_rate.clone()).unwrap_err();
+    assert_eq!(err, ContractError::InvalidMintFeeRate);
+
+    let err = execute::set_fee_rate(deps.as_mut(), fee_rate).unwrap_err();
+    assert_eq!(err, ContractError::InvalidMintFeeRate);
+
+    // a mint fee rate of exactly one is allowed
+    execute::set_fee_rate(
+        deps.as_mut(),
+        FeeRate {
+            metadata: Decimal::zero(),
+            key: Decimal::zero(),
+            mint: Decimal::one(),
+        },
+    )
+    .unwrap();
+}
+
 #[test]
 fn setting_nft() {
     let mut deps = mock_dependencies();
@@ -64,3 +105,231 @@ fn setting_nft() {
         assert_eq!(err, ContractError::DoubleInit);
     }
 }
+
+/// A badge as stored by v1.2
+#[derive(Serialize, Deserialize)]
+struct LegacyBadge {
+    manager: Addr,
+    metadata: Metadata,
+    transferrable: bool,
+    rule: LegacyMintRule,
+    expiry: Option<u64>,
+    max_supply: Option<u64>,
+    current_supply: u64,
+}
+
+/// A mint rule as stored by v1.2
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LegacyMintRule {
+    ByKey(String),
+    ByKeys,
+}
+
+fn mock_legacy_badge(rule: LegacyMintRule, current_supply: u64) -> LegacyBadge {
+    LegacyBadge {
+        manager: Addr::unchecked("larry"),
+        metadata: Metadata::default(),
+        transferrable: true,
+        rule,
+        expiry: None,
+        max_supply: None,
+        current_supply,
+    }
+}
+
+fn migrate_batch(deps: DepsMut) -> Result<Response, ContractError> {
+    let msg = MigrateMsg {
+        limit: Some(2),
+    };
+    contract::migrate(deps, mock_env(), msg)
+}
+
+#[test]
+fn migrating_in_batches() {
+    let mut deps = mock_dependencies();
+
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.2.0").unwrap();
+
+    // three badges under the "by keys" rule, one under the "by key" rule, and two whitelisted keys
+    let legacy_badges: Map<u64, LegacyBadge> = Map::new("badges");
+    let legacy_keys: Set<(u64, &str)> = Set::new("keys");
+    for id in 1..=3 {
+        let badge = mock_legacy_badge(LegacyMintRule::ByKeys, id);
+        legacy_badges.save(deps.as_mut().storage, id, &badge).unwrap();
+    }
+    let badge = mock_legacy_badge(LegacyMintRule::ByKey(KEY.into()), 4);
+    legacy_badges.save(deps.as_mut().storage, 4, &badge).unwrap();
+    BADGE_COUNT.save(deps.as_mut().storage, &4).unwrap();
+    legacy_keys.insert(deps.as_mut().storage, (3, "1234")).unwrap();
+    legacy_keys.insert(deps.as_mut().storage, (3, "5678")).unwrap();
+
+    // a limit of zero would never make progress
+    {
+        let msg = MigrateMsg {
+            limit: Some(0),
+        };
+        let err = contract::migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMigrationLimit);
+    }
+
+    // first batch converts two badges
+    {
+        let res = migrate_batch(deps.as_mut()).unwrap();
+        assert!(res.attributes.contains(&attr("badges_migrated", "2")));
+        assert!(res.attributes.contains(&attr("keys_migrated", "0")));
+        assert!(res.attributes.contains(&attr("complete", "false")));
+
+        let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, "1.2.0");
+    }
+
+    // second batch converts the other two badges
+    {
+        let res = migrate_batch(deps.as_mut()).unwrap();
+        assert!(res.attributes.contains(&attr("badges_migrated", "2")));
+        assert!(res.attributes.contains(&attr("keys_migrated", "0")));
+        assert!(res.attributes.contains(&attr("complete", "false")));
+    }
+
+    // third batch converts the two keys
+    {
+        let res = migrate_batch(deps.as_mut()).unwrap();
+        assert!(res.attributes.contains(&attr("badges_migrated", "0")));
+        assert!(res.attributes.contains(&attr("keys_migrated", "2")));
+        assert!(res.attributes.contains(&attr("complete", "false")));
+    }
+
+    // fourth batch finds nothing left and completes the migration
+    {
+        let res = migrate_batch(deps.as_mut()).unwrap();
+        assert!(res.attributes.contains(&attr("badges_migrated", "0")));
+        assert!(res.attributes.contains(&attr("keys_migrated", "0")));
+        assert!(res.attributes.contains(&attr("complete", "true")));
+
+        let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, CONTRACT_VERSION);
+    }
+
+    // all entries are in the new format
+    for id in 1..=4 {
+        let badge = BADGES.load(deps.as_ref().storage, id).unwrap();
+        assert_eq!(badge.manager, Some(Addr::unchecked("larry")));
+        assert_eq!(badge.current_supply, id);
+        assert_eq!(badge.message_version, MessageVersion::Legacy);
+        if id == 4 {
+            assert_eq!(badge.rule, MintRule::by_key(KEY));
+        } else {
+            assert_eq!(badge.rule, MintRule::ByKeys);
+        }
+    }
+    for key in ["1234", "5678"] {
+        let key_info = KEYS.load(deps.as_ref().storage, (3, key)).unwrap();
+        assert_eq!(key_info.key_type, KeyType::Secp256k1);
+    }
+
+    // the migration can't be run again
+    {
+        let err = migrate_batch(deps.as_mut()).unwrap_err();
+        assert_eq!(err, ContractError::incorrect_contract_version("1.2.0", CONTRACT_VERSION));
+    }
+}
+
+#[test]
+fn migrating_with_badges_created_in_between() {
+    let mut deps = mock_dependencies();
+
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.2.0").unwrap();
+    NFT.save(deps.as_mut().storage, &Addr::unchecked("nft")).unwrap();
+    FEE_RATE
+        .save(
+            deps.as_mut().storage,
+            &FeeRate {
+                metadata: Decimal::zero(),
+                key: Decimal::zero(),
+                mint: Decimal::zero(),
+            },
+        )
+        .unwrap();
+
+    // two badges under the "by keys" rule, and one whitelisted key
+    let legacy_badges: Map<u64, LegacyBadge> = Map::new("badges");
+    let legacy_keys: Set<(u64, &str)> = Set::new("keys");
+    for id in 1..=2 {
+        let badge = mock_legacy_badge(LegacyMintRule::ByKeys, id);
+        legacy_badges.save(deps.as_mut().storage, id, &badge).unwrap();
+    }
+    BADGE_COUNT.save(deps.as_mut().storage, &2).unwrap();
+    legacy_keys.insert(deps.as_mut().storage, (1, "1234")).unwrap();
+
+    // first batch converts both badges
+    {
+        let res = migrate_batch(deps.as_mut()).unwrap();
+        assert!(res.attributes.contains(&attr("badges_migrated", "2")));
+        assert!(res.attributes.contains(&attr("complete", "false")));
+    }
+
+    // a badge is created, and keys are added to both a converted and the new badge
+    let key_info = KeyInfo {
+        key_type: KeyType::Secp256k1,
+        uses: 3,
+        expiry: None,
+        tier: Some("gold".into()),
+    };
+    let badge = Badge {
+        manager: Some(Addr::unchecked("larry")),
+        metadata: Metadata::default(),
+        transferrable: true,
+        rule: MintRule::ByKeys,
+        start: None,
+        expiry: None,
+        phases: vec![],
+        max_supply: Some(100),
+        current_supply: 0,
+        paused: false,
+        message_version: MessageVersion::V2,
+        max_per_user: Some(1),
+        require_consent: false,
+    };
+    {
+        let info = mock_info("larry", &[]);
+        let res = execute::create_badge(deps.as_mut(), mock_env(), info, badge.clone()).unwrap();
+        assert!(res.attributes.contains(&attr("id", "3")));
+
+        for id in [1, 3] {
+            execute::add_keys(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("larry", &[]),
+                id,
+                BTreeSet::from([KEY.to_string()]),
+                key_info.clone(),
+            )
+            .unwrap();
+        }
+    }
+
+    // the remaining batches convert only the legacy key
+    {
+        let res = migrate_batch(deps.as_mut()).unwrap();
+        assert!(res.attributes.contains(&attr("badges_migrated", "0")));
+        assert!(res.attributes.contains(&attr("keys_migrated", "1")));
+        assert!(res.attributes.contains(&attr("complete", "false")));
+
+        let res = migrate_batch(deps.as_mut()).unwrap();
+        assert!(res.attributes.contains(&attr("keys_migrated", "0")));
+        assert!(res.attributes.contains(&attr("complete", "true")));
+    }
+
+    // the new badge and keys are left untouched
+    {
+        assert_eq!(BADGES.load(deps.as_ref().storage, 3).unwrap(), badge);
+
+        for id in [1, 3] {
+            assert_eq!(KEYS.load(deps.as_ref().storage, (id, KEY)).unwrap(), key_info);
+        }
+
+        let key_info = KEYS.load(deps.as_ref().storage, (1, "1234")).unwrap();
+        assert_eq!(key_info, KeyInfo::new(KeyType::Secp256k1));
+    }
+}
@@ -2,7 +2,8 @@ use std::collections::BTreeSet;
 
 use cosmwasm_std::testing::{mock_dependencies, mock_info, MockApi, MockQuerier, MockStorage};
 use cosmwasm_std::{
-    coins, to_binary, Addr, BankMsg, Decimal, DepsMut, Empty, Event, OwnedDeps, SubMsg, Uint128,
+    coin, coins, to_binary, Addr, BankMsg, Decimal, DepsMut, Empty, Event, OwnedDeps, SubMsg,
+    Uint128,
 };
 use cw_utils::PaymentError;
 use k256::ecdsa::VerifyingKey;
@@ -13,7 +14,7 @@ use sg_std::{create_fund_fairburn_pool_msg, Response, NATIVE_DENOM};
 use badge_hub::error::ContractError;
 use badge_hub::{execute, query};
 use badge_hub::state::*;
-use badges::{Badge, MintRule, FeeRate};
+use badges::{Badge, KeyInfo, KeyType, MessageVersion, MintRule, FeeRate};
 
 mod utils;
 
@@ -22,6 +23,7 @@ fn mock_fee_rate() -> FeeRate {
     FeeRate {
         metadata: Decimal::from_ratio(10u128, 1u128),
         key: Decimal::from_ratio(2u128, 1u128),
+        mint: Decimal::zero(),
     }
 }
 
@@ -69,13 +71,19 @@ fn badge_creation_fee() {
     let mut deps = setup_test();
 
     let mock_badge = Badge {
-        manager: Addr::unchecked("manager"),
+        manager: Some(Addr::unchecked("manager")),
         metadata: Metadata::default(),
         transferrable: false,
         rule: MintRule::ByKeys,
+        start: None,
         expiry: None,
+        phases: vec![],
         max_supply: None,
         current_supply: 0,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
     };
 
     let mut create = |amount: u128, denom: &str| -> Result<Response, ContractError> {
@@ -159,13 +167,19 @@ fn badge_editing_fee() {
     };
 
     let mock_badge = Badge {
-        manager: Addr::unchecked("manager"),
+        manager: Some(Addr::unchecked("manager")),
         metadata: old_metadata.clone(),
         transferrable: false,
         rule: MintRule::ByKeys,
+        start: None,
         expiry: None,
+        phases: vec![],
         max_supply: None,
         current_supply: 0,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
     };
 
     BADGES.save(deps.as_mut().storage, 1, &mock_badge).unwrap();
@@ -219,13 +233,19 @@ fn key_adding_fee() {
     let mut deps = setup_test();
 
     let mock_badge = Badge {
-        manager: Addr::unchecked("manager"),
+        manager: Some(Addr::unchecked("manager")),
         metadata: Metadata::default(),
         transferrable: false,
         rule: MintRule::ByKeys,
+        start: None,
         expiry: None,
+        phases: vec![],
         max_supply: None,
         current_supply: 0,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
     };
 
     BADGES.save(deps.as_mut().storage, 1, &mock_badge).unwrap();
@@ -243,12 +263,21 @@ fn key_adding_fee() {
         .cloned()
         .collect::<BTreeSet<_>>();
 
-    let bytes = to_binary(&mock_keys).unwrap().len() as u128;
+    // the fee covers the key info stored along with each key
+    let key_info = KeyInfo {
+        key_type: KeyType::Secp256k1,
+        uses: 5,
+        expiry: Some(12345),
+        tier: Some("gold".into()),
+    };
+    let entries = mock_keys_set.iter().map(|key| (key, &key_info)).collect::<Vec<_>>();
+    let bytes = to_binary(&entries).unwrap().len() as u128;
     let fee_amount = (Uint128::new(bytes) * mock_fee_rate().key).u128();
 
     fn add(
         deps: DepsMut,
         keys: &BTreeSet<String>,
+        key_info: &KeyInfo,
         amount: u128,
     ) -> Result<Response, ContractError> {
         execute::add_keys(
@@ -257,6 +286,7 @@ fn key_adding_fee() {
             mock_info("manager", &coins(amount, NATIVE_DENOM)),
             1,
             keys.clone(),
+            key_info.clone(),
         )
     }
 
@@ -264,16 +294,244 @@ fn key_adding_fee() {
     {
         let insufficient_amount = fee_amount * 9 / 10;
 
-        let err = add(deps.as_mut(), &mock_keys_set, insufficient_amount).unwrap_err();
+        let err = add(deps.as_mut(), &mock_keys_set, &key_info, insufficient_amount).unwrap_err();
         assert_eq!(err, FeeError::InsufficientFee(fee_amount, insufficient_amount).into());
     }
 
     // sending sufficient fee
     {
-        let res = add(deps.as_mut(), &mock_keys_set, fee_amount).unwrap();
+        let res = add(deps.as_mut(), &mock_keys_set, &key_info, fee_amount).unwrap();
         assert_correct_sg1_output(&res, fee_amount);
 
-        let res = query::key(deps.as_ref(), 1, &mock_keys[7]);
+        let res = query::key(deps.as_ref(), 1, &mock_keys[7]).unwrap();
         assert!(res.whitelisted);
     }
 }
+
+#[test]
+fn key_rotating_fee() {
+    let mut deps = setup_test();
+
+    let old_key = hex::encode(VerifyingKey::from(&utils::random_privkey()).to_bytes());
+    let new_key = hex::encode(VerifyingKey::from(&utils::random_privkey()).to_bytes());
+
+    let mock_badge = Badge {
+        manager: Some(Addr::unchecked("manager")),
+        metadata: Metadata::default(),
+        transferrable: false,
+        rule: MintRule::ByKey {
+            key: old_key,
+            key_type: KeyType::Secp256k1,
+        },
+        start: None,
+        expiry: None,
+        phases: vec![],
+        max_supply: None,
+        current_supply: 0,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
+    };
+
+    BADGES.save(deps.as_mut().storage, 1, &mock_badge).unwrap();
+
+    // the new key is charged in full, even though it is the same size as the old one
+    let bytes = to_binary(&new_key).unwrap().len() as u128;
+    let fee_amount = (Uint128::new(bytes) * mock_fee_rate().key).u128();
+    assert!(fee_amount > 0);
+
+    fn rotate(deps: DepsMut, key: &str, amount: u128) -> Result<Response, ContractError> {
+        execute::rotate_key(
+            deps,
+            utils::mock_env_at_timestamp(10000),
+            mock_info("manager", &coins(amount, NATIVE_DENOM)),
+            1,
+            key.to_string(),
+            KeyType::Secp256k1,
+        )
+    }
+
+    // not sending sufficient fee
+    {
+        let insufficient_amount = fee_amount * 9 / 10;
+
+        let err = rotate(deps.as_mut(), &new_key, insufficient_amount).unwrap_err();
+        assert_eq!(err, FeeError::InsufficientFee(fee_amount, insufficient_amount).into());
+    }
+
+    // sending sufficient fee
+    {
+        let res = rotate(deps.as_mut(), &new_key, fee_amount).unwrap();
+        assert_correct_sg1_output(&res, fee_amount);
+
+        let badge = BADGES.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(
+            badge.rule,
+            MintRule::ByKey {
+                key: new_key,
+                key_type: KeyType::Secp256k1,
+            },
+        );
+    }
+}
+
+#[test]
+fn instance_revoking_fee() {
+    let mut deps = setup_test();
+
+    let mock_badge = Badge {
+        manager: Some(Addr::unchecked("manager")),
+        metadata: Metadata::default(),
+        transferrable: false,
+        rule: MintRule::by_minter("manager"),
+        start: None,
+        expiry: None,
+        phases: vec![],
+        max_supply: None,
+        current_supply: 2,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
+    };
+
+    BADGES.save(deps.as_mut().storage, 1, &mock_badge).unwrap();
+
+    let reason = Some("cheated".to_string());
+    let bytes = to_binary("cheated").unwrap().len() as u128;
+    let fee_amount = (Uint128::new(bytes) * mock_fee_rate().metadata).u128();
+
+    fn revoke(
+        deps: DepsMut,
+        serial: u64,
+        reason: Option<String>,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        execute::revoke_instance(
+            deps,
+            mock_info("manager", &coins(amount, NATIVE_DENOM)),
+            1,
+            serial,
+            reason,
+        )
+    }
+
+    // not sending sufficient fee for the reason
+    {
+        let insufficient_amount = fee_amount * 9 / 10;
+
+        let err = revoke(deps.as_mut(), 1, reason.clone(), insufficient_amount).unwrap_err();
+        assert_eq!(err, FeeError::InsufficientFee(fee_amount, insufficient_amount).into());
+    }
+
+    // sending sufficient fee for the reason
+    {
+        let res = revoke(deps.as_mut(), 1, reason, fee_amount).unwrap();
+        assert_correct_sg1_output(&res, fee_amount);
+    }
+
+    // no fee is charged if no reason is given
+    {
+        let res = revoke(deps.as_mut(), 2, None, 0).unwrap();
+        assert!(res.messages.is_empty());
+    }
+}
+
+#[test]
+fn payment_minting_fee() {
+    let mut deps = setup_test();
+
+    // 5% of the price goes to the protocol
+    FEE_RATE
+        .save(
+            deps.as_mut().storage,
+            &FeeRate {
+                mint: Decimal::percent(5),
+                ..mock_fee_rate()
+            },
+        )
+        .unwrap();
+
+    let mock_badge = Badge {
+        manager: Some(Addr::unchecked("manager")),
+        metadata: Metadata::default(),
+        transferrable: false,
+        rule: MintRule::ByPayment {
+            price: coin(1000, NATIVE_DENOM),
+            recipient: "manager".to_string(),
+        },
+        start: None,
+        expiry: None,
+        phases: vec![],
+        max_supply: None,
+        current_supply: 0,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
+    };
+
+    BADGES.save(deps.as_mut().storage, 1, &mock_badge).unwrap();
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            2,
+            &Badge {
+                rule: MintRule::ByPayment {
+                    price: coin(1000, "uatom"),
+                    recipient: "manager".to_string(),
+                },
+                ..mock_badge
+            },
+        )
+        .unwrap();
+
+    // badge priced in ustars: the protocol fee is deducted from the proceeds
+    {
+        let res = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(1000, NATIVE_DENOM)),
+            1,
+            "jake".to_string(),
+        )
+        .unwrap();
+
+        // fair burn output, then proceeds to the recipient, then the mint message
+        assert_correct_sg1_output(
+            &Response::new().add_submessages(res.messages[..3].to_vec()).add_events(res.events),
+            50,
+        );
+        assert_eq!(
+            res.messages[3],
+            SubMsg::new(BankMsg::Send {
+                to_address: "manager".to_string(),
+                amount: coins(950, NATIVE_DENOM),
+            }),
+        );
+        assert_eq!(res.messages.len(), 5);
+    }
+
+    // badge priced in other denoms: no protocol fee is charged
+    {
+        let res = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(1000, "uatom")),
+            2,
+            "jake".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(BankMsg::Send {
+                to_address: "manager".to_string(),
+                amount: coins(1000, "uatom"),
+            }),
+        );
+        assert_eq!(res.messages.len(), 2);
+        assert!(res.events.is_empty());
+    }
+}
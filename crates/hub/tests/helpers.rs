@@ -1,24 +1,33 @@
-use cosmwasm_std::testing::mock_dependencies;
-use cosmwasm_std::Addr;
+use std::collections::BTreeSet;
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{coin, coins, Addr, Binary, Uint128};
+use cw_utils::PaymentError;
 use k256::ecdsa::VerifyingKey;
 use sg_metadata::Metadata;
 
 use badge_hub::error::ContractError;
 use badge_hub::helpers::*;
-use badge_hub::state::{KEYS, OWNERS};
-use badges::{Badge, MintRule};
+use badge_hub::state::{BADGES, COLLECTION_TOKENS, KEYS, OWNERS, REVOCATIONS};
+use badges::{Badge, Denom, KeyInfo, KeyType, MessageVersion, MintRule, Phase};
 
 mod utils;
 
 fn mock_badge(rule: Option<MintRule>, expiry: Option<u64>, max_supply: Option<u64>) -> Badge {
     Badge {
-        manager: Addr::unchecked("larry"),
+        manager: Some(Addr::unchecked("larry")),
         metadata: Metadata::default(),
         transferrable: true,
         rule: rule.unwrap_or(MintRule::ByKeys),
+        start: None,
         expiry,
+        phases: vec![],
         max_supply,
         current_supply: 99,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
     }
 }
 
@@ -80,26 +89,166 @@ fn asserting_availability_max_supply() {
     assert_eq!(assert_unavailable(&badge, &env.block), Ok(()));
 }
 
+/// Badge has a minting start time
+#[test]
+fn asserting_availability_start() {
+    let mut badge = mock_badge(None, Some(20000), None);
+    badge.start = Some(10000);
+
+    // minting has not started
+    let env = utils::mock_env_at_timestamp(9999);
+    assert_eq!(assert_available(&badge, &env.block, 1), Err(ContractError::NotStarted));
+    assert_eq!(assert_not_ended(&badge, &env.block), Ok(()));
+    assert_eq!(assert_unavailable(&badge, &env.block), Err(ContractError::Available));
+
+    // minting has started
+    let env = utils::mock_env_at_timestamp(10000);
+    assert_eq!(assert_available(&badge, &env.block, 1), Ok(()));
+    assert_eq!(assert_unavailable(&badge, &env.block), Err(ContractError::Available));
+}
+
+/// Badge has multiple minting phases
+#[test]
+fn asserting_availability_phases() {
+    // mock badge has a current supply of 99
+    let mut badge = mock_badge(None, None, Some(200));
+    badge.phases = vec![
+        Phase {
+            start: 10000,
+            expiry: Some(19999),
+            max_supply: Some(100),
+        },
+        Phase {
+            start: 30000,
+            expiry: Some(39999),
+            max_supply: None,
+        },
+    ];
+
+    // before the first phase
+    let env = utils::mock_env_at_timestamp(9999);
+    assert_eq!(assert_available(&badge, &env.block, 1), Err(ContractError::NotStarted));
+    assert_eq!(assert_unavailable(&badge, &env.block), Err(ContractError::Available));
+
+    // during the first phase, which has its own supply cap
+    let env = utils::mock_env_at_timestamp(15000);
+    assert_eq!(assert_available(&badge, &env.block, 1), Ok(()));
+    assert_eq!(assert_available(&badge, &env.block, 2), Err(ContractError::PhaseSoldOut));
+    assert_eq!(assert_unavailable(&badge, &env.block), Err(ContractError::Available));
+
+    // between the two phases
+    let env = utils::mock_env_at_timestamp(25000);
+    assert_eq!(assert_available(&badge, &env.block, 1), Err(ContractError::NotStarted));
+    assert_eq!(assert_unavailable(&badge, &env.block), Err(ContractError::Available));
+
+    // during the second phase, only the badge's max supply applies
+    let env = utils::mock_env_at_timestamp(35000);
+    assert_eq!(assert_available(&badge, &env.block, 101), Ok(()));
+    assert_eq!(assert_available(&badge, &env.block, 102), Err(ContractError::SoldOut));
+
+    // after all phases have ended
+    let env = utils::mock_env_at_timestamp(40000);
+    assert_eq!(assert_available(&badge, &env.block, 1), Err(ContractError::Expired));
+    assert_eq!(assert_unavailable(&badge, &env.block), Ok(()));
+
+    // once the supply caps of the ongoing and all future phases have been reached, the badge can
+    // never be minted again, even though it has not expired
+    badge.current_supply = 100;
+    badge.phases[1].max_supply = Some(100);
+    for timestamp in [9999, 15000, 25000, 35000] {
+        let env = utils::mock_env_at_timestamp(timestamp);
+        assert_eq!(assert_not_ended(&badge, &env.block), Err(ContractError::SoldOut));
+        assert_eq!(assert_unavailable(&badge, &env.block), Ok(()));
+    }
+
+    // unless a future phase still has supply left
+    badge.phases[1].max_supply = Some(101);
+    let env = utils::mock_env_at_timestamp(15000);
+    assert_eq!(assert_available(&badge, &env.block, 1), Err(ContractError::PhaseSoldOut));
+    assert_eq!(assert_unavailable(&badge, &env.block), Err(ContractError::Available));
+}
+
+#[test]
+fn asserting_valid_schedule() {
+    let phase = |start: u64, expiry: Option<u64>| Phase {
+        start,
+        expiry,
+        max_supply: None,
+    };
+
+    let mut badge = mock_badge(None, Some(10000), None);
+
+    // start after expiry
+    badge.start = Some(10001);
+    assert_eq!(assert_valid_schedule(&badge), Err(ContractError::InvalidSchedule));
+    badge.start = Some(10000);
+    assert_eq!(assert_valid_schedule(&badge), Ok(()));
+
+    // phase ends before it starts
+    badge.phases = vec![phase(200, Some(100))];
+    assert_eq!(assert_valid_schedule(&badge), Err(ContractError::InvalidSchedule));
+
+    // phases overlap
+    badge.phases = vec![phase(100, Some(200)), phase(200, Some(300))];
+    assert_eq!(assert_valid_schedule(&badge), Err(ContractError::InvalidSchedule));
+
+    // phases not sorted
+    badge.phases = vec![phase(300, Some(400)), phase(100, Some(200))];
+    assert_eq!(assert_valid_schedule(&badge), Err(ContractError::InvalidSchedule));
+
+    // a phase other than the last one is open-ended
+    badge.phases = vec![phase(100, None), phase(300, Some(400))];
+    assert_eq!(assert_valid_schedule(&badge), Err(ContractError::InvalidSchedule));
+
+    // valid phases
+    badge.phases = vec![phase(100, Some(200)), phase(201, None)];
+    assert_eq!(assert_valid_schedule(&badge), Ok(()));
+}
+
 #[test]
 fn asserting_eligible() {
     let mut deps = mock_dependencies();
 
     let id = 1;
     let user = "larry";
+    let mut badge = mock_badge(None, None, None);
 
     // user has not claimed
     {
-        assert_eq!(assert_eligible(deps.as_ref().storage, id, user), Ok(()));
+        assert_eq!(assert_eligible(deps.as_ref().storage, id, &badge, user), Ok(()));
     }
 
     // user has already claimed
     {
         OWNERS.insert(deps.as_mut().storage, (id, user)).unwrap();
+        assert_eq!(claim_count(deps.as_ref().storage, id, user), Ok(1));
         assert_eq!(
-            assert_eligible(deps.as_ref().storage, id, user),
+            assert_eligible(deps.as_ref().storage, id, &badge, user),
             Err(ContractError::already_claimed(id, user)),
         );
     }
+
+    // user can claim up to the badge's limit
+    {
+        badge.max_per_user = Some(3);
+        assert_eq!(assert_eligible(deps.as_ref().storage, id, &badge, user), Ok(()));
+
+        record_claim(deps.as_mut().storage, id, user).unwrap();
+        assert_eq!(assert_eligible(deps.as_ref().storage, id, &badge, user), Ok(()));
+
+        record_claim(deps.as_mut().storage, id, user).unwrap();
+        assert_eq!(claim_count(deps.as_ref().storage, id, user), Ok(3));
+        assert_eq!(
+            assert_eligible(deps.as_ref().storage, id, &badge, user),
+            Err(ContractError::already_claimed(id, user)),
+        );
+    }
+
+    // user can claim any number of times if the badge has no limit
+    {
+        badge.max_per_user = None;
+        assert_eq!(assert_eligible(deps.as_ref().storage, id, &badge, user), Ok(()));
+    }
 }
 
 #[test]
@@ -119,6 +268,45 @@ fn asserting_user_can_mint() {
     }
 }
 
+#[test]
+fn generating_claim_messages() {
+    let env = mock_env();
+    let mut badge = mock_badge(None, None, None);
+
+    // legacy badges use the legacy format, ignoring the deadline
+    {
+        assert_eq!(claim_message(&env, 1, &badge, "larry", Some(12345)), message(1, "larry"));
+    }
+
+    // v2 badges specify the chain, the hub, and the deadline
+    {
+        badge.message_version = MessageVersion::V2;
+        assert_eq!(
+            claim_message(&env, 1, &badge, "larry", Some(12345)),
+            "badges claim message v2\n\
+             chain: cosmos-testnet-14002\n\
+             hub: cosmos2contract\n\
+             badge: 1\n\
+             user: larry\n\
+             deadline: 12345",
+        );
+        assert_eq!(
+            claim_message(&env, 1, &badge, "larry", None),
+            message_v2("cosmos-testnet-14002", "cosmos2contract", 1, "larry", None),
+        );
+        assert!(claim_message(&env, 1, &badge, "larry", None).ends_with("deadline: none"));
+    }
+}
+
+#[test]
+fn asserting_before_deadline() {
+    let block = utils::mock_env_at_timestamp(10000).block;
+
+    assert_eq!(assert_before_deadline(&block, None), Ok(()));
+    assert_eq!(assert_before_deadline(&block, Some(10000)), Ok(()));
+    assert_eq!(assert_before_deadline(&block, Some(9999)), Err(ContractError::DeadlinePassed));
+}
+
 #[test]
 fn asserting_can_mint_by_key() {
     let deps = mock_dependencies();
@@ -127,7 +315,7 @@ fn asserting_can_mint_by_key() {
     let pubkey = VerifyingKey::from(&privkey);
     let pubkey_str = hex::encode(pubkey.to_bytes());
 
-    let rule = MintRule::ByKey(pubkey_str);
+    let rule = MintRule::by_key(pubkey_str);
     let id = 1;
     let badge = mock_badge(Some(rule), None, None);
 
@@ -137,7 +325,10 @@ fn asserting_can_mint_by_key() {
 
     // use the correct privkey, msg, and an unused salts
     {
-        assert_eq!(assert_can_mint_by_key(deps.as_ref().api, id, &badge, owner, &signature), Ok(()));
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Ok(()),
+        );
     }
 
     // use the correct privkey but sign the wrong message
@@ -145,7 +336,7 @@ fn asserting_can_mint_by_key() {
         let false_msg = message(id, "jake");
         let signature = utils::sign(&privkey, &false_msg);
         assert_eq!(
-            assert_can_mint_by_key(deps.as_ref().api, id, &badge, owner, &signature),
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
             Err(ContractError::InvalidSignature),
         );
     }
@@ -155,7 +346,7 @@ fn asserting_can_mint_by_key() {
         let false_privkey = utils::random_privkey();
         let signature = utils::sign(&false_privkey, &msg);
         assert_eq!(
-            assert_can_mint_by_key(deps.as_ref().api, id, &badge, owner, &signature),
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
             Err(ContractError::InvalidSignature),
         );
     }
@@ -173,17 +364,27 @@ fn asserting_can_mint_by_keys() {
     let rule = MintRule::ByKeys;
     let id = 1;
     let badge = mock_badge(Some(rule), None, None);
+    let block = mock_env().block;
 
     let owner = "larry";
     let msg = message(id, owner);
     let signature = utils::sign(&privkey, &msg);
 
-    KEYS.insert(deps.as_mut().storage, (id, &pubkey_str)).unwrap();
+    KEYS.save(deps.as_mut().storage, (id, &pubkey_str), &KeyInfo::new(KeyType::Secp256k1)).unwrap();
 
     // use a whitelisted key and sign the correct message
     {
         assert_eq!(
-            assert_can_mint_by_keys(deps.as_ref(), id, &badge, owner, &pubkey_str, &signature),
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &pubkey_str,
+                &msg,
+                &signature
+            ),
             Ok(()),
         );
     }
@@ -193,7 +394,16 @@ fn asserting_can_mint_by_keys() {
         let false_msg = "ngmi";
         let signature = utils::sign(&privkey, false_msg);
         assert_eq!(
-            assert_can_mint_by_keys(deps.as_ref(), id, &badge, owner, &pubkey_str, &signature),
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &pubkey_str,
+                &msg,
+                &signature
+            ),
             Err(ContractError::InvalidSignature),
         );
     }
@@ -205,8 +415,994 @@ fn asserting_can_mint_by_keys() {
         let false_pubkey_str = hex::encode(false_pubkey.to_bytes());
         let signature = utils::sign(&false_privkey, &msg);
         assert_eq!(
-            assert_can_mint_by_keys(deps.as_ref(), id, &badge, owner, &false_pubkey_str, &signature),
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &false_pubkey_str,
+                &msg,
+                &signature
+            ),
             Err(ContractError::key_does_not_exist(id)),
         );
     }
 }
+
+#[test]
+fn asserting_can_mint_by_ed25519_key() {
+    let mut deps = mock_dependencies();
+
+    let (privkey, pubkey_str) = utils::random_ed25519_privkey();
+
+    let rule = MintRule::ByKey {
+        key: pubkey_str.clone(),
+        key_type: KeyType::Ed25519,
+    };
+    let id = 1;
+    let badge = mock_badge(Some(rule), None, None);
+
+    let owner = "larry";
+    let msg = message(id, owner);
+    let signature = utils::sign_ed25519(&privkey, &msg);
+
+    // sign the correct message with the Ed25519 key
+    {
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Ok(()),
+        );
+    }
+
+    // sign the wrong message
+    {
+        let false_msg = message(id, "jake");
+        let signature = utils::sign_ed25519(&privkey, &false_msg);
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // a secp256k1 signature is not accepted for an Ed25519 key
+    {
+        let signature = utils::sign(&utils::mock_privkey(), &msg);
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // Ed25519 keys can be whitelisted for the "by keys" rule, alongside secp256k1 keys
+    {
+        let badge = mock_badge(Some(MintRule::ByKeys), None, None);
+        let block = mock_env().block;
+        KEYS.save(deps.as_mut().storage, (id, &pubkey_str), &KeyInfo::new(KeyType::Ed25519))
+            .unwrap();
+        assert_eq!(
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &pubkey_str,
+                &msg,
+                &signature
+            ),
+            Ok(()),
+        );
+    }
+}
+
+#[test]
+fn asserting_can_mint_by_adr036_key() {
+    let deps = mock_dependencies();
+
+    let privkey = utils::mock_privkey();
+    let pubkey = VerifyingKey::from(&privkey);
+    let pubkey_bytes = pubkey.to_bytes().to_vec();
+
+    let rule = MintRule::ByKey {
+        key: hex::encode(&pubkey_bytes),
+        key_type: KeyType::Adr036,
+    };
+    let id = 1;
+    let badge = mock_badge(Some(rule), None, None);
+
+    let owner = "stars1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5t7mrdd";
+    let msg = message(id, owner);
+
+    // the signer is the wallet address of the key, under the same prefix as the owner
+    let signer = derive_address(&pubkey_bytes, owner).unwrap();
+    assert!(signer.starts_with("stars1"));
+
+    // the wallet signs the sign doc wrapping the correct message
+    {
+        let signature = utils::sign(&privkey, &adr036_sign_doc(&signer, &msg));
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Ok(()),
+        );
+    }
+
+    // a signature over the raw message is not accepted
+    {
+        let signature = utils::sign(&privkey, &msg);
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // the sign doc must name the key's own address as the signer
+    {
+        let signature = utils::sign(&privkey, &adr036_sign_doc(owner, &msg));
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // the sign doc must wrap the message for the correct user
+    {
+        let signature = utils::sign(&privkey, &adr036_sign_doc(&signer, &message(id, "jake")));
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // the owner must be a bech32 address, for the signer address to be derived
+    {
+        let msg = message(id, "larry");
+        let signature = utils::sign(&privkey, &adr036_sign_doc(&signer, &msg));
+        assert!(matches!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, "larry", &msg, &signature),
+            Err(ContractError::Bech32(_)),
+        ));
+    }
+}
+
+#[test]
+fn asserting_can_mint_by_ethereum_key() {
+    let mut deps = mock_dependencies();
+
+    let privkey = utils::random_privkey();
+    let address = utils::eth_address(&privkey);
+
+    let id = 1;
+    let badge = mock_badge(Some(MintRule::ByKeys), None, None);
+    let block = mock_env().block;
+
+    let owner = "larry";
+    let msg = message(id, owner);
+
+    KEYS.save(deps.as_mut().storage, (id, &address), &KeyInfo::new(KeyType::Ethereum)).unwrap();
+
+    // the address recovered from the signature matches the whitelisted one
+    {
+        let signature = utils::sign_eip191(&privkey, &msg);
+        assert_eq!(
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &address,
+                &msg,
+                &signature
+            ),
+            Ok(()),
+        );
+    }
+
+    // the submitted address may be prefixed with 0x, and in any case
+    {
+        let signature = utils::sign_eip191(&privkey, &msg);
+        assert_eq!(
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &format!("0x{}", address.to_uppercase()),
+                &msg,
+                &signature
+            ),
+            Ok(()),
+        );
+    }
+
+    // recovery ids of 0 and 1 are accepted as well
+    {
+        let mut sig_bytes = hex::decode(utils::sign_eip191(&privkey, &msg)).unwrap();
+        sig_bytes[64] -= 27;
+        let signature = hex::encode(sig_bytes);
+        assert_eq!(
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &address,
+                &msg,
+                &signature
+            ),
+            Ok(()),
+        );
+    }
+
+    // the signature must bind the owner's address
+    {
+        let signature = utils::sign_eip191(&privkey, &message(id, "jake"));
+        assert_eq!(
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &address,
+                &msg,
+                &signature
+            ),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // a signature by another address is not accepted
+    {
+        let signature = utils::sign_eip191(&utils::random_privkey(), &msg);
+        assert_eq!(
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &address,
+                &msg,
+                &signature
+            ),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // a signature without the EIP-191 prefix is not accepted
+    {
+        let mut sig_bytes = hex::decode(utils::sign(&privkey, &msg)).unwrap();
+        sig_bytes.push(27);
+        let signature = hex::encode(sig_bytes);
+        assert_eq!(
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &address,
+                &msg,
+                &signature
+            ),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // a signature without the recovery id is not accepted
+    {
+        let signature = utils::sign_eip191(&privkey, &msg);
+        let signature = &signature[..128];
+        assert_eq!(
+            assert_can_mint_by_keys(
+                deps.as_ref(),
+                &block,
+                id,
+                &badge,
+                owner,
+                &address,
+                &msg,
+                signature
+            ),
+            Err(ContractError::InvalidSignature),
+        );
+    }
+
+    // an Ethereum key also works for the "by key" rule
+    {
+        let badge = mock_badge(
+            Some(MintRule::ByKey {
+                key: address,
+                key_type: KeyType::Ethereum,
+            }),
+            None,
+            None,
+        );
+        let signature = utils::sign_eip191(&privkey, &msg);
+        assert_eq!(
+            assert_can_mint_by_key(deps.as_ref().api, &badge, owner, &msg, &signature),
+            Ok(()),
+        );
+    }
+}
+
+#[test]
+fn asserting_can_mint_by_merkle_proof() {
+    let mut deps = mock_dependencies();
+
+    let leaves = ["larry", "jake", "pumpkin:3", "doge", "pepe"];
+    let (root, proofs) = utils::merkle_tree(&leaves);
+
+    let rule = MintRule::ByMerkleRoot {
+        root,
+    };
+    let badge = mock_badge(Some(rule), None, None);
+
+    let assert = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, owner, proof, amount| {
+        assert_can_mint_by_merkle_proof(deps.as_ref().storage, 1, &badge, owner, proof, amount)
+    };
+
+    // every address in the tree can be proven
+    {
+        for (owner, proof) in ["larry", "jake"].iter().zip(&proofs) {
+            assert_eq!(assert(&deps, owner, proof, None), Ok(()));
+        }
+        for (owner, proof) in ["doge", "pepe"].iter().zip(&proofs[3..]) {
+            assert_eq!(assert(&deps, owner, proof, None), Ok(()));
+        }
+        assert_eq!(assert(&deps, "pumpkin", &proofs[2], Some(3)), Ok(()));
+    }
+
+    // an address not in the tree cannot use another address' proof
+    {
+        assert_eq!(
+            assert(&deps, "satoshi", &proofs[0], None),
+            Err(ContractError::InvalidMerkleProof),
+        );
+    }
+
+    // an address in the tree cannot use another address' proof
+    {
+        assert_eq!(
+            assert(&deps, "larry", &proofs[2], None),
+            Err(ContractError::InvalidMerkleProof),
+        );
+    }
+
+    // the amount must match the one committed to in the leaf
+    {
+        assert_eq!(
+            assert(&deps, "pumpkin", &proofs[2], Some(4)),
+            Err(ContractError::InvalidMerkleProof),
+        );
+        assert_eq!(
+            assert(&deps, "pumpkin", &proofs[2], None),
+            Err(ContractError::InvalidMerkleProof),
+        );
+        assert_eq!(
+            assert(&deps, "larry", &proofs[0], Some(1)),
+            Err(ContractError::InvalidMerkleProof),
+        );
+    }
+
+    // a leaf without an amount can be used once
+    {
+        record_claim(deps.as_mut().storage, 1, "larry").unwrap();
+        assert_eq!(
+            assert(&deps, "larry", &proofs[0], None),
+            Err(ContractError::already_claimed(1, "larry")),
+        );
+    }
+
+    // a leaf with an amount can be used as many times as the amount
+    {
+        for _ in 0..3 {
+            assert_eq!(assert(&deps, "pumpkin", &proofs[2], Some(3)), Ok(()));
+            record_claim(deps.as_mut().storage, 1, "pumpkin").unwrap();
+        }
+        assert_eq!(
+            assert(&deps, "pumpkin", &proofs[2], Some(3)),
+            Err(ContractError::already_claimed(1, "pumpkin")),
+        );
+    }
+
+    // the badge must use the "by merkle root" rule
+    {
+        let badge = mock_badge(Some(MintRule::ByKeys), None, None);
+        assert_eq!(
+            assert_can_mint_by_merkle_proof(
+                deps.as_ref().storage,
+                1,
+                &badge,
+                "jake",
+                &proofs[1],
+                None,
+            ),
+            Err(ContractError::wrong_mint_rule("by_merkle_root", &MintRule::ByKeys)),
+        );
+    }
+}
+
+#[test]
+fn asserting_can_mint_by_payment() {
+    let rule = MintRule::ByPayment {
+        price: coin(100, "ustars"),
+        recipient: "larry".to_string(),
+    };
+    let badge = mock_badge(Some(rule), None, None);
+
+    // pay the exact price
+    {
+        let info = mock_info("jake", &coins(100, "ustars"));
+        assert_eq!(
+            assert_can_mint_by_payment(&badge, &info),
+            Ok((&coin(100, "ustars"), "larry", Uint128::new(100))),
+        );
+    }
+
+    // overpay
+    {
+        let info = mock_info("jake", &coins(150, "ustars"));
+        assert_eq!(
+            assert_can_mint_by_payment(&badge, &info),
+            Ok((&coin(100, "ustars"), "larry", Uint128::new(150))),
+        );
+    }
+
+    // underpay
+    {
+        let info = mock_info("jake", &coins(99, "ustars"));
+        assert_eq!(
+            assert_can_mint_by_payment(&badge, &info),
+            Err(ContractError::insufficient_payment(coin(100, "ustars"), coin(99, "ustars"))),
+        );
+    }
+
+    // pay nothing
+    {
+        let info = mock_info("jake", &[]);
+        assert_eq!(
+            assert_can_mint_by_payment(&badge, &info),
+            Err(PaymentError::NoFunds {}.into()),
+        );
+    }
+
+    // pay in the wrong denom
+    {
+        let info = mock_info("jake", &coins(100, "uatom"));
+        assert_eq!(
+            assert_can_mint_by_payment(&badge, &info),
+            Err(PaymentError::MissingDenom("ustars".to_string()).into()),
+        );
+    }
+
+    // pay in multiple denoms
+    {
+        let info = mock_info("jake", &[coin(100, "ustars"), coin(100, "uatom")]);
+        assert_eq!(
+            assert_can_mint_by_payment(&badge, &info),
+            Err(PaymentError::MultipleDenoms {}.into()),
+        );
+    }
+
+    // the badge must use the "by payment" rule
+    {
+        let badge = mock_badge(Some(MintRule::ByKeys), None, None);
+        let info = mock_info("jake", &coins(100, "ustars"));
+        assert_eq!(
+            assert_can_mint_by_payment(&badge, &info),
+            Err(ContractError::wrong_mint_rule("by_payment", &MintRule::ByKeys)),
+        );
+    }
+}
+
+#[test]
+fn asserting_can_mint_by_oracle() {
+    let mut deps = mock_dependencies();
+
+    deps.querier.update_wasm(utils::mock_oracle(&["larry"]));
+
+    let querier = deps.as_ref().querier;
+    let rule = MintRule::ByOracle {
+        contract: "oracle".to_string(),
+    };
+    let id = 1;
+    let badge = mock_badge(Some(rule), None, None);
+
+    // the oracle deems the user eligible
+    {
+        assert_eq!(assert_can_mint_by_oracle(&querier, id, &badge, "larry", None), Ok(()));
+    }
+
+    // the oracle deems the user eligible based on the proof
+    {
+        let proof = Binary::from(b"ekaj");
+        assert_eq!(assert_can_mint_by_oracle(&querier, id, &badge, "jake", Some(proof)), Ok(()));
+    }
+
+    // the oracle deems the user not eligible
+    {
+        let proof = Binary::from(b"jake");
+        assert_eq!(
+            assert_can_mint_by_oracle(&querier, id, &badge, "jake", Some(proof)),
+            Err(ContractError::not_eligible(id, "jake")),
+        );
+    }
+
+    // the badge must use the "by oracle" rule
+    {
+        let badge = mock_badge(Some(MintRule::ByKeys), None, None);
+        assert_eq!(
+            assert_can_mint_by_oracle(&querier, id, &badge, "larry", None),
+            Err(ContractError::wrong_mint_rule("by_oracle", &MintRule::ByKeys)),
+        );
+    }
+}
+
+#[test]
+fn asserting_can_mint_by_collection() {
+    let mut deps = mock_dependencies();
+
+    deps.querier.update_wasm(utils::mock_nft(&[("larry", "69"), ("jake", "420")]));
+
+    let rule = MintRule::ByCollection {
+        collection: "collection".to_string(),
+    };
+    let id = 1;
+    let badge = mock_badge(Some(rule), None, None);
+
+    // the owner owns the token
+    {
+        assert_eq!(assert_can_mint_by_collection(deps.as_ref(), id, &badge, "larry", "69"), Ok(()));
+    }
+
+    // the owner doesn't own the token
+    {
+        assert_eq!(
+            assert_can_mint_by_collection(deps.as_ref(), id, &badge, "larry", "420"),
+            Err(ContractError::not_token_owner("420", "larry")),
+        );
+    }
+
+    // the token has already been used
+    {
+        COLLECTION_TOKENS.insert(deps.as_mut().storage, (id, "69")).unwrap();
+        assert_eq!(
+            assert_can_mint_by_collection(deps.as_ref(), id, &badge, "larry", "69"),
+            Err(ContractError::token_used(id, "69")),
+        );
+    }
+
+    // the badge must use the "by collection" rule
+    {
+        let badge = mock_badge(Some(MintRule::ByKeys), None, None);
+        assert_eq!(
+            assert_can_mint_by_collection(deps.as_ref(), id, &badge, "jake", "420"),
+            Err(ContractError::wrong_mint_rule("by_collection", &MintRule::ByKeys)),
+        );
+    }
+}
+
+#[test]
+fn asserting_can_mint_by_balance() {
+    let mut deps = mock_dependencies();
+
+    deps.querier.update_balance("larry", coins(100, "ustars"));
+    deps.querier.update_balance("jake", coins(99, "ustars"));
+    deps.querier.update_wasm(utils::mock_cw20(&[("larry", 99), ("jake", 100)]));
+
+    let querier = deps.as_ref().querier;
+
+    // native coin
+    {
+        let rule = MintRule::ByBalance {
+            denom: Denom::Native("ustars".to_string()),
+            amount: Uint128::new(100),
+        };
+        let badge = mock_badge(Some(rule), None, None);
+        assert_eq!(assert_can_mint_by_balance(&querier, &badge, "larry"), Ok(()));
+        assert_eq!(
+            assert_can_mint_by_balance(&querier, &badge, "jake"),
+            Err(ContractError::insufficient_balance("jake", Uint128::new(99), Uint128::new(100))),
+        );
+        assert_eq!(
+            assert_can_mint_by_balance(&querier, &badge, "pumpkin"),
+            Err(ContractError::insufficient_balance("pumpkin", Uint128::zero(), Uint128::new(100))),
+        );
+    }
+
+    // cw20 token
+    {
+        let rule = MintRule::ByBalance {
+            denom: Denom::Cw20("token".to_string()),
+            amount: Uint128::new(100),
+        };
+        let badge = mock_badge(Some(rule), None, None);
+        assert_eq!(assert_can_mint_by_balance(&querier, &badge, "jake"), Ok(()));
+        assert_eq!(
+            assert_can_mint_by_balance(&querier, &badge, "larry"),
+            Err(ContractError::insufficient_balance("larry", Uint128::new(99), Uint128::new(100))),
+        );
+    }
+
+    // the badge must use the "by balance" rule
+    {
+        let badge = mock_badge(Some(MintRule::ByKeys), None, None);
+        assert_eq!(
+            assert_can_mint_by_balance(&querier, &badge, "larry"),
+            Err(ContractError::wrong_mint_rule("by_balance", &MintRule::ByKeys)),
+        );
+    }
+}
+
+#[test]
+fn asserting_valid_rule() {
+    let mut deps = mock_dependencies();
+
+    BADGES.save(deps.as_mut().storage, 1, &mock_badge(None, None, None)).unwrap();
+
+    assert_eq!(assert_valid_rule(deps.as_ref(), &MintRule::ByKeys), Ok(()));
+    assert_eq!(assert_valid_rule(deps.as_ref(), &MintRule::by_minter("larry")), Ok(()));
+
+    // an Ed25519 pubkey must be 32 bytes long
+    {
+        let (_, pubkey_str) = utils::random_ed25519_privkey();
+        assert_eq!(
+            assert_valid_rule(deps.as_ref(), &MintRule::ByKey {
+                key: pubkey_str,
+                key_type: KeyType::Ed25519,
+            }),
+            Ok(()),
+        );
+
+        let pubkey = VerifyingKey::from(&utils::mock_privkey());
+        assert_eq!(
+            assert_valid_rule(deps.as_ref(), &MintRule::ByKey {
+                key: hex::encode(pubkey.to_bytes()),
+                key_type: KeyType::Ed25519,
+            }),
+            Err(ContractError::InvalidPubkey),
+        );
+    }
+
+    // an Ethereum key must be a 20-byte address
+    {
+        let address = utils::eth_address(&utils::mock_privkey());
+        assert_eq!(
+            assert_valid_rule(deps.as_ref(), &MintRule::ByKey {
+                key: address.clone(),
+                key_type: KeyType::Ethereum,
+            }),
+            Ok(()),
+        );
+        assert_eq!(
+            assert_valid_rule(deps.as_ref(), &MintRule::ByKey {
+                key: format!("0x{}", address),
+                key_type: KeyType::Ethereum,
+            }),
+            Err(ContractError::FromHex(hex::FromHexError::InvalidHexCharacter {
+                c: 'x',
+                index: 1,
+            })),
+        );
+        assert_eq!(
+            assert_valid_rule(deps.as_ref(), &MintRule::ByKey {
+                key: address[2..].to_string(),
+                key_type: KeyType::Ethereum,
+            }),
+            Err(ContractError::InvalidPubkey),
+        );
+    }
+
+    // an ADR-036 pubkey must be a compressed secp256k1 pubkey
+    {
+        let pubkey = VerifyingKey::from(&utils::mock_privkey());
+        assert_eq!(
+            assert_valid_rule(deps.as_ref(), &MintRule::ByKey {
+                key: hex::encode(pubkey.to_bytes()),
+                key_type: KeyType::Adr036,
+            }),
+            Ok(()),
+        );
+
+        // the uncompressed form is a valid secp256k1 pubkey, but not accepted for ADR-036
+        let uncompressed = format!("04{}", "ab".repeat(64));
+        assert_eq!(
+            assert_valid_rule(deps.as_ref(), &MintRule::ByKey {
+                key: uncompressed.clone(),
+                key_type: KeyType::Secp256k1,
+            }),
+            Ok(()),
+        );
+        assert_eq!(
+            assert_valid_rule(deps.as_ref(), &MintRule::ByKey {
+                key: uncompressed,
+                key_type: KeyType::Adr036,
+            }),
+            Err(ContractError::InvalidPubkey),
+        );
+    }
+
+    let root = hex::encode(hash("larry"));
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByMerkleRoot {
+            root,
+        }),
+        Ok(()),
+    );
+
+    // root must be exactly 32 bytes
+    let root = hex::encode([0u8; 20]);
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByMerkleRoot {
+            root,
+        }),
+        Err(ContractError::InvalidMerkleRoot),
+    );
+
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByPayment {
+            price: coin(100, "ustars"),
+            recipient: "larry".to_string(),
+        }),
+        Ok(()),
+    );
+
+    // price must not be zero
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByPayment {
+            price: coin(0, "ustars"),
+            recipient: "larry".to_string(),
+        }),
+        Err(ContractError::InvalidPrice),
+    );
+
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByPrerequisites {
+            all_of: BTreeSet::from([1]),
+            any_of: BTreeSet::new(),
+        }),
+        Ok(()),
+    );
+
+    // at least one prerequisite must be specified
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByPrerequisites {
+            all_of: BTreeSet::new(),
+            any_of: BTreeSet::new(),
+        }),
+        Err(ContractError::InvalidPrerequisites),
+    );
+
+    // prerequisites must exist
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByPrerequisites {
+            all_of: BTreeSet::from([1]),
+            any_of: BTreeSet::from([2]),
+        }),
+        Err(ContractError::InvalidPrerequisites),
+    );
+
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByBalance {
+            denom: Denom::Cw20("token".to_string()),
+            amount: Uint128::new(100),
+        }),
+        Ok(()),
+    );
+
+    // amount must not be zero
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByBalance {
+            denom: Denom::Native("ustars".to_string()),
+            amount: Uint128::zero(),
+        }),
+        Err(ContractError::InvalidBalanceRequirement),
+    );
+
+    // native denom must not be empty
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::ByBalance {
+            denom: Denom::Native("".to_string()),
+            amount: Uint128::new(100),
+        }),
+        Err(ContractError::InvalidBalanceRequirement),
+    );
+
+    // composite rules are valid if all sub-rules are valid
+    assert_eq!(
+        assert_valid_rule(
+            deps.as_ref(),
+            &MintRule::All(vec![
+                MintRule::ByKeys,
+                MintRule::Any(vec![MintRule::by_minter("larry"), MintRule::ByAllowlist]),
+            ]),
+        ),
+        Ok(()),
+    );
+
+    // the sub-rules of an "any" rule may need proofs of the same kind, as only one takes effect
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::Any(vec![MintRule::ByKeys, MintRule::ByKeys])),
+        Ok(()),
+    );
+
+    // the sub-rules of an "all" rule must not need proofs of the same kind, including in nested
+    // rules, as they would be checked against the same proof
+    assert_eq!(
+        assert_valid_rule(
+            deps.as_ref(),
+            &MintRule::All(vec![
+                MintRule::ByKeys,
+                MintRule::Any(vec![MintRule::by_minter("larry"), MintRule::ByKeys]),
+            ]),
+        ),
+        Err(ContractError::InvalidCompositeRule),
+    );
+
+    // composite rules must not be empty
+    assert_eq!(
+        assert_valid_rule(deps.as_ref(), &MintRule::Any(vec![])),
+        Err(ContractError::InvalidCompositeRule),
+    );
+
+    // sub-rules must be valid
+    assert_eq!(
+        assert_valid_rule(
+            deps.as_ref(),
+            &MintRule::All(vec![MintRule::ByKeys, MintRule::by_key("deadbeef")]),
+        ),
+        Err(ContractError::InvalidPubkey),
+    );
+
+    // composite rules can contain at most one "by payment" rule, including in nested rules
+    let payment_rule = MintRule::ByPayment {
+        price: coin(100, "ustars"),
+        recipient: "larry".to_string(),
+    };
+    assert_eq!(
+        assert_valid_rule(
+            deps.as_ref(),
+            &MintRule::All(vec![
+                payment_rule.clone(),
+                MintRule::Any(vec![MintRule::ByKeys, payment_rule]),
+            ]),
+        ),
+        Err(ContractError::InvalidCompositeRule),
+    );
+}
+
+#[test]
+fn asserting_can_mint_by_prerequisites() {
+    let mut deps = mock_dependencies();
+
+    deps.querier.update_wasm(utils::mock_nft(&[
+        ("larry", "1|1"),
+        ("larry", "12|3"),
+        ("larry", "15|2"),
+        ("jake", "12|1"),
+        ("jake", "120|1"),
+        ("jake", "16|5"),
+        ("pumpkin", "1|2"),
+    ]));
+
+    let nft_addr = Addr::unchecked("nft");
+    let store = deps.as_ref().storage;
+    let querier = deps.as_ref().querier;
+
+    let rule = MintRule::ByPrerequisites {
+        all_of: BTreeSet::from([12]),
+        any_of: BTreeSet::from([15, 16]),
+    };
+    let badge = mock_badge(Some(rule), None, None);
+
+    // holds all of the `all_of` and one of the `any_of`
+    {
+        assert_eq!(
+            assert_can_mint_by_prerequisites(store, &querier, &nft_addr, &badge, "larry"),
+            Ok(()),
+        );
+        assert_eq!(
+            assert_can_mint_by_prerequisites(store, &querier, &nft_addr, &badge, "jake"),
+            Ok(()),
+        );
+    }
+
+    // missing one of the `all_of`
+    {
+        assert_eq!(
+            assert_can_mint_by_prerequisites(store, &querier, &nft_addr, &badge, "pumpkin"),
+            Err(ContractError::missing_prerequisite(12, "pumpkin")),
+        );
+    }
+
+    // holds an instance of badge 120, which must not be confused with badge 12
+    {
+        let rule = MintRule::ByPrerequisites {
+            all_of: BTreeSet::from([12, 120]),
+            any_of: BTreeSet::new(),
+        };
+        let badge = mock_badge(Some(rule), None, None);
+        assert_eq!(
+            assert_can_mint_by_prerequisites(store, &querier, &nft_addr, &badge, "jake"),
+            Ok(()),
+        );
+        assert_eq!(
+            assert_can_mint_by_prerequisites(store, &querier, &nft_addr, &badge, "larry"),
+            Err(ContractError::missing_prerequisite(120, "larry")),
+        );
+    }
+
+    // holds none of the `any_of`
+    {
+        let rule = MintRule::ByPrerequisites {
+            all_of: BTreeSet::new(),
+            any_of: BTreeSet::from([15, 16]),
+        };
+        let badge = mock_badge(Some(rule), None, None);
+        assert_eq!(
+            assert_can_mint_by_prerequisites(store, &querier, &nft_addr, &badge, "pumpkin"),
+            Err(ContractError::no_prerequisite("pumpkin")),
+        );
+    }
+
+    // the badge must use the "by prerequisites" rule
+    {
+        let badge = mock_badge(Some(MintRule::ByKeys), None, None);
+        assert_eq!(
+            assert_can_mint_by_prerequisites(store, &querier, &nft_addr, &badge, "larry"),
+            Err(ContractError::wrong_mint_rule("by_prerequisites", &MintRule::ByKeys)),
+        );
+    }
+    // revoked instances don't count, even if the user's tokens span multiple pages
+    {
+        let token_ids = (1..=12).map(|serial| format!("12|{serial}")).collect::<Vec<_>>();
+        let tokens =
+            token_ids.iter().map(|token_id| ("hans", token_id.as_str())).collect::<Vec<_>>();
+        deps.querier.update_wasm(utils::mock_nft(&tokens));
+
+        // in lexicographical order, 12|9 is the last of the user's tokens
+        for serial in 1..=8 {
+            REVOCATIONS.save(deps.as_mut().storage, (12, serial), &None).unwrap();
+        }
+        for serial in 10..=12 {
+            REVOCATIONS.save(deps.as_mut().storage, (12, serial), &None).unwrap();
+        }
+
+        let rule = MintRule::ByPrerequisites {
+            all_of: BTreeSet::from([12]),
+            any_of: BTreeSet::new(),
+        };
+        let badge = mock_badge(Some(rule), None, None);
+
+        let deps_ref = deps.as_ref();
+        assert_eq!(
+            assert_can_mint_by_prerequisites(
+                deps_ref.storage,
+                &deps_ref.querier,
+                &nft_addr,
+                &badge,
+                "hans",
+            ),
+            Ok(()),
+        );
+
+        REVOCATIONS.save(deps.as_mut().storage, (12, 9), &None).unwrap();
+
+        let deps_ref = deps.as_ref();
+        assert_eq!(
+            assert_can_mint_by_prerequisites(
+                deps_ref.storage,
+                &deps_ref.querier,
+                &nft_addr,
+                &badge,
+                "hans",
+            ),
+            Err(ContractError::missing_prerequisite(12, "hans")),
+        );
+    }
+}
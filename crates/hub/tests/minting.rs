@@ -1,14 +1,22 @@
 use cosmwasm_std::testing::{mock_dependencies, MockApi, MockQuerier, MockStorage};
-use cosmwasm_std::{attr, to_binary, Addr, Empty, OwnedDeps, StdResult, Storage, SubMsg, WasmMsg};
+use std::collections::BTreeSet;
+
+use cosmwasm_std::testing::mock_info;
+use cosmwasm_std::{
+    attr, coin, coins, from_binary, to_binary, Addr, BankMsg, Binary, Decimal, DepsMut, Empty,
+    OwnedDeps, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
 use k256::ecdsa::{SigningKey, VerifyingKey};
 use cw721_base::MintMsg;
 use sg_metadata::Metadata;
+use sg_std::Response;
 
 use badge_hub::error::ContractError;
-use badge_hub::helpers::{message, token_id};
+use badge_hub::helpers::{derive_address, message, message_v2, token_id, voucher_message};
 use badge_hub::state::*;
 use badge_hub::{execute, query};
-use badges::{Badge, MintRule};
+use badges::hub::{BatchMintResponse, FailedClaim, KeyClaim, SignatureClaim};
+use badges::{Badge, Denom, FeeRate, KeyInfo, KeyType, MessageVersion, MintProof, MintRule};
 
 mod utils;
 
@@ -20,6 +28,16 @@ fn mock_keys() -> (SigningKey, VerifyingKey, String) {
     (privkey, pubkey, pubkey_str)
 }
 
+fn set_badge_paused(store: &mut dyn Storage, id: u64, paused: bool) {
+    BADGES
+        .update(store, id, |badge| {
+            let mut badge = badge.unwrap();
+            badge.paused = paused;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+}
+
 fn set_badge_supply(store: &mut dyn Storage, id: u64, current_supply: u64) {
     BADGES
         .update(store, id, |badge| {
@@ -36,13 +54,19 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
     NFT.save(deps.as_mut().storage, &Addr::unchecked("nft")).unwrap();
 
     let default_badge = Badge {
-        manager: Addr::unchecked("larry"),
+        manager: Some(Addr::unchecked("larry")),
         metadata: Metadata::default(),
         transferrable: true,
         rule: MintRule::ByKeys,
+        start: None,
         expiry: Some(12345),
+        phases: vec![],
         max_supply: Some(100),
         current_supply: 98,
+        paused: false,
+        message_version: MessageVersion::Legacy,
+        max_per_user: Some(1),
+        require_consent: false,
     };
 
     let (_, _, pubkey_str) = mock_keys();
@@ -63,7 +87,7 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
             deps.as_mut().storage,
             2,
             &Badge {
-                rule: MintRule::ByKey(pubkey_str.clone()),
+                rule: MintRule::by_key(pubkey_str.clone()),
                 ..default_badge.clone()
             },
         )
@@ -80,7 +104,7 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
         )
         .unwrap();
 
-    KEYS.insert(deps.as_mut().storage, (3, &pubkey_str)).unwrap();
+    KEYS.save(deps.as_mut().storage, (3, &pubkey_str), &KeyInfo::new(KeyType::Secp256k1)).unwrap();
 
     deps
 }
@@ -185,6 +209,358 @@ fn minting_by_minter() {
     }
 }
 
+#[test]
+fn minting_with_consent() {
+    let mut deps = setup_test();
+
+    BADGES
+        .update(deps.as_mut().storage, 1, |badge| {
+            let mut badge = badge.unwrap();
+            badge.require_consent = true;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    // minter makes offers instead of minting
+    {
+        let res = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["pumpkin", "jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_minter"),
+                attr("id", "1"),
+                attr("offered", "2"),
+            ],
+        );
+
+        let badge = BADGES.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(badge.current_supply, 98);
+
+        let res = query::offers(deps.as_ref(), 1, None, None).unwrap();
+        assert_eq!(res.users, vec!["jake".to_string(), "pumpkin".to_string()]);
+    }
+
+    // minter cannot bypass consent using the generic mint message
+    {
+        let err = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            1,
+            "doge".to_string(),
+            vec![],
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ConsentRequired);
+    }
+
+    // user without an offer cannot accept or reject
+    {
+        let err = execute::accept_offer(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("doge", &[]),
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::no_offer(1, "doge"));
+
+        let err = execute::reject_offer(deps.as_mut(), mock_info("doge", &[]), 1).unwrap_err();
+        assert_eq!(err, ContractError::no_offer(1, "doge"));
+    }
+
+    // cannot accept an offer after the badge expires
+    {
+        let err = execute::accept_offer(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            mock_info("jake", &[]),
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Expired);
+    }
+
+    // recipient accepts the offer
+    {
+        let res = execute::accept_offer(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "1|99".to_string(),
+                    owner: "jake".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/accept_offer"),
+                attr("id", "1"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        let res = query::offer(deps.as_ref(), 1, "jake");
+        assert!(!res.pending);
+    }
+
+    // recipient rejects the offer
+    {
+        let res = execute::reject_offer(deps.as_mut(), mock_info("pumpkin", &[]), 1).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/reject_offer"),
+                attr("id", "1"),
+                attr("user", "pumpkin"),
+            ],
+        );
+
+        let res = query::offer(deps.as_ref(), 1, "pumpkin");
+        assert!(!res.pending);
+
+        let badge = BADGES.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(badge.current_supply, 99);
+    }
+}
+
+#[test]
+fn minting_by_voucher() {
+    let mut deps = setup_test();
+
+    let (privkey, pubkey, pubkey_str) = mock_keys();
+    let minter = derive_address(&pubkey.to_bytes(), "stars1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5t7mrdd")
+        .unwrap();
+
+    BADGES
+        .update(deps.as_mut().storage, 1, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::ByMinter(minter.clone());
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    let env = utils::mock_env_at_timestamp(10000);
+    let (chain_id, hub) = (env.block.chain_id.clone(), env.contract.address.clone());
+    let sign_voucher = |privkey: &SigningKey, id: u64, owner: &str, nonce: u64| {
+        utils::sign(privkey, &voucher_message(&chain_id, &hub, id, owner, nonce, 11000))
+    };
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            env.clone(),
+            3,
+            "jake".to_string(),
+            1,
+            11000,
+            pubkey_str.clone(),
+            sign_voucher(&privkey, 3, "jake", 1),
+            Addr::unchecked("relayer"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_minter", &MintRule::ByKeys));
+    }
+
+    // voucher signed by a key other than the minter's
+    {
+        let false_privkey = utils::random_privkey();
+        let false_pubkey_str = hex::encode(VerifyingKey::from(&false_privkey).to_bytes());
+
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            env.clone(),
+            1,
+            "jake".to_string(),
+            1,
+            11000,
+            false_pubkey_str,
+            sign_voucher(&false_privkey, 1, "jake", 1),
+            Addr::unchecked("relayer"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotMinter);
+    }
+
+    // voucher issued to a different owner
+    {
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            env.clone(),
+            1,
+            "jake".to_string(),
+            1,
+            11000,
+            pubkey_str.clone(),
+            sign_voucher(&privkey, 1, "pumpkin", 1),
+            Addr::unchecked("relayer"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // voucher redeemed after its deadline
+    {
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(11001),
+            1,
+            "jake".to_string(),
+            1,
+            11000,
+            pubkey_str.clone(),
+            sign_voucher(&privkey, 1, "jake", 1),
+            Addr::unchecked("relayer"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DeadlinePassed);
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_voucher(
+            deps.as_mut(),
+            env.clone(),
+            1,
+            "jake".to_string(),
+            1,
+            11000,
+            pubkey_str.clone(),
+            sign_voucher(&privkey, 1, "jake", 1),
+            Addr::unchecked("relayer"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "1|99".to_string(),
+                    owner: "jake".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_voucher"),
+                attr("id", "1"),
+                attr("serial", "99"),
+                attr("nonce", "1"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        // the nonce should be marked as used
+        let res = query::voucher(deps.as_ref(), 1, 1);
+        assert!(res.used);
+    }
+
+    // attempt to redeem the same voucher again
+    {
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            env.clone(),
+            1,
+            "jake".to_string(),
+            1,
+            11000,
+            pubkey_str.clone(),
+            sign_voucher(&privkey, 1, "jake", 1),
+            Addr::unchecked("relayer"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::nonce_used(1, 1));
+    }
+
+    // a voucher with a different nonce can be redeemed by the same owner
+    {
+        execute::mint_by_voucher(
+            deps.as_mut(),
+            env.clone(),
+            1,
+            "jake".to_string(),
+            2,
+            11000,
+            pubkey_str.clone(),
+            sign_voucher(&privkey, 1, "jake", 2),
+            Addr::unchecked("relayer"),
+        )
+        .unwrap();
+
+        let badge = BADGES.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(badge.current_supply, 100);
+    }
+
+    // if the badge requires consent, only the owner can redeem the voucher
+    {
+        BADGES
+            .update(deps.as_mut().storage, 1, |badge| {
+                let mut badge = badge.unwrap();
+                badge.require_consent = true;
+                badge.max_supply = None;
+                StdResult::Ok(badge)
+            })
+            .unwrap();
+
+        let err = execute::mint_by_voucher(
+            deps.as_mut(),
+            env.clone(),
+            1,
+            "pumpkin".to_string(),
+            3,
+            11000,
+            pubkey_str.clone(),
+            sign_voucher(&privkey, 1, "pumpkin", 3),
+            Addr::unchecked("relayer"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ConsentRequired);
+
+        execute::mint_by_voucher(
+            deps.as_mut(),
+            env,
+            1,
+            "pumpkin".to_string(),
+            3,
+            11000,
+            pubkey_str,
+            sign_voucher(&privkey, 1, "pumpkin", 3),
+            Addr::unchecked("pumpkin"),
+        )
+        .unwrap();
+    }
+}
+
 #[test]
 fn minting_by_key() {
     let mut deps = setup_test();
@@ -201,6 +577,7 @@ fn minting_by_key() {
             3,
             "larry".to_string(),
             signature.clone(),
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::wrong_mint_rule("by_key", &MintRule::ByKeys));
@@ -217,6 +594,7 @@ fn minting_by_key() {
             2,
             "larry".to_string(),
             signature,
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::InvalidSignature);
@@ -233,6 +611,7 @@ fn minting_by_key() {
             2,
             "larry".to_string(),
             signature,
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::InvalidSignature);
@@ -246,6 +625,7 @@ fn minting_by_key() {
             2,
             "larry".to_string(),
             signature.clone(),
+            None,
         )
         .unwrap();
         assert_eq!(
@@ -277,7 +657,7 @@ fn minting_by_key() {
         assert_eq!(badge.current_supply, 99);
 
         // larry should be marked as already received
-        let res = query::owner(deps.as_ref(), 2, "larry");
+        let res = query::owner(deps.as_ref(), 2, "larry").unwrap();
         assert!(res.claimed);
     }
 
@@ -289,6 +669,7 @@ fn minting_by_key() {
             2,
             "larry".to_string(),
             signature.clone(),
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::already_claimed(2, "larry"));
@@ -302,6 +683,7 @@ fn minting_by_key() {
             2,
             "larry".to_string(),
             signature.clone(),
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::Expired);
@@ -317,6 +699,7 @@ fn minting_by_key() {
             2,
             "larry".to_string(),
             signature,
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::SoldOut);
@@ -324,25 +707,192 @@ fn minting_by_key() {
 }
 
 #[test]
-fn minting_by_keys() {
+fn minting_up_to_max_per_user() {
     let mut deps = setup_test();
 
-    let (privkey, _, pubkey_str) = mock_keys();
-    let msg = message(3, "larry");
-    let signature = utils::sign(&privkey, &msg);
+    BADGES
+        .update(deps.as_mut().storage, 3, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::ByAllowlist;
+            badge.max_supply = None;
+            badge.max_per_user = Some(2);
+            StdResult::Ok(badge)
+        })
+        .unwrap();
 
-    // wrong mint rule
-    {
-        let err = execute::mint_by_key(
-            deps.as_mut(),
+    ALLOWLIST.insert(deps.as_mut().storage, (3, "larry")).unwrap();
+
+    let mint = |deps: DepsMut| {
+        execute::mint_by_allowlist(
+            deps,
             utils::mock_env_at_timestamp(10000),
-            1,
-            "larry".to_string(),
-            signature.clone(),
+            3,
+            Addr::unchecked("larry"),
         )
-        .unwrap_err();
-        assert_eq!(
-            err,
+    };
+
+    // the user can claim twice
+    for count in 1..=2 {
+        mint(deps.as_mut()).unwrap();
+
+        let res = query::owner(deps.as_ref(), 3, "larry").unwrap();
+        assert!(res.claimed);
+        assert_eq!(res.count, count);
+    }
+
+    // but not a third time
+    {
+        let err = mint(deps.as_mut()).unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(3, "larry"));
+    }
+}
+
+#[test]
+fn minting_by_key_with_v2_message() {
+    let mut deps = setup_test();
+
+    BADGES
+        .update(deps.as_mut().storage, 2, |badge| {
+            let mut badge = badge.unwrap();
+            badge.message_version = MessageVersion::V2;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    let env = utils::mock_env_at_timestamp(10000);
+    let chain_id = env.block.chain_id.clone();
+    let hub = env.contract.address.clone();
+    let privkey = utils::mock_privkey();
+    let msg = message_v2(&chain_id, &hub, 2, "larry", Some(10001));
+
+    // the claim message query returns the message in the v2 format
+    {
+        let res = query::claim_message(deps.as_ref(), env.clone(), 2, "larry", Some(10001));
+        let res = res.unwrap();
+        assert_eq!(res.version, MessageVersion::V2);
+        assert_eq!(res.message, msg);
+    }
+
+    // a signature of the legacy message is not accepted
+    {
+        let signature = utils::sign(&privkey, &message(2, "larry"));
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            env.clone(),
+            2,
+            "larry".to_string(),
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // a signature for another chain is not accepted
+    {
+        let signature = utils::sign(&privkey, &message_v2("stargaze-1", &hub, 2, "larry", None));
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            env.clone(),
+            2,
+            "larry".to_string(),
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // a signature for another hub contract is not accepted
+    {
+        let signature = utils::sign(&privkey, &message_v2(&chain_id, "hub2", 2, "larry", None));
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            env.clone(),
+            2,
+            "larry".to_string(),
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // the deadline must match the one that was signed
+    {
+        let signature = utils::sign(&privkey, &msg);
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            env.clone(),
+            2,
+            "larry".to_string(),
+            signature,
+            Some(20000),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // a signature whose deadline has passed is not accepted
+    {
+        let signature = utils::sign(&privkey, &message_v2(&chain_id, &hub, 2, "larry", Some(9999)));
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            env.clone(),
+            2,
+            "larry".to_string(),
+            signature,
+            Some(9999),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DeadlinePassed);
+    }
+
+    // properly mint before the deadline
+    {
+        let signature = utils::sign(&privkey, &msg);
+        let res = execute::mint_by_key(
+            deps.as_mut(),
+            env,
+            2,
+            "larry".to_string(),
+            signature,
+            Some(10001),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_key"),
+                attr("id", "2"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+    }
+}
+
+#[test]
+fn minting_by_keys() {
+    let mut deps = setup_test();
+
+    let (privkey, _, pubkey_str) = mock_keys();
+    let msg = message(3, "larry");
+    let signature = utils::sign(&privkey, &msg);
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            "larry".to_string(),
+            signature.clone(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
             ContractError::wrong_mint_rule("by_key", &MintRule::by_minter("larry")),
         );
     }
@@ -359,6 +909,7 @@ fn minting_by_keys() {
             "larry".to_string(),
             pubkey_str.clone(),
             signature,
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::InvalidSignature);
@@ -378,6 +929,7 @@ fn minting_by_keys() {
             "larry".to_string(),
             false_pubkey_str,
             signature,
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::key_does_not_exist(3));
@@ -392,6 +944,7 @@ fn minting_by_keys() {
             "larry".to_string(),
             pubkey_str.clone(),
             signature.clone(),
+            None,
         )
         .unwrap();
         assert_eq!(
@@ -423,11 +976,11 @@ fn minting_by_keys() {
         assert_eq!(badge.current_supply, 99);
 
         // larry should be marked as already received
-        let res = query::owner(deps.as_ref(), 3, "larry");
+        let res = query::owner(deps.as_ref(), 3, "larry").unwrap();
         assert!(res.claimed);
 
         // the pubkey should be removed from the whitelist
-        let res = query::key(deps.as_ref(), 3, &pubkey_str);
+        let res = query::key(deps.as_ref(), 3, &pubkey_str).unwrap();
         assert!(!res.whitelisted);
     }
 
@@ -443,6 +996,7 @@ fn minting_by_keys() {
             "jake".to_string(),
             pubkey_str.clone(),
             signature,
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::key_does_not_exist(3));
@@ -450,7 +1004,7 @@ fn minting_by_keys() {
 
     // attempt to mint to the same user again
     {
-        KEYS.insert(deps.as_mut().storage, (3, "larry")).unwrap();
+        KEYS.save(deps.as_mut().storage, (3, "larry"), &KeyInfo::new(KeyType::Secp256k1)).unwrap();
 
         let err = execute::mint_by_keys(
             deps.as_mut(),
@@ -459,6 +1013,7 @@ fn minting_by_keys() {
         "larry".to_string(),
             pubkey_str,
             signature.clone(),
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::already_claimed(3, "larry"));
@@ -472,6 +1027,7 @@ fn minting_by_keys() {
             3,
             "larry".to_string(),
             signature.clone(),
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::Expired);
@@ -487,8 +1043,1735 @@ fn minting_by_keys() {
             3,
             "larry".to_string(),
             signature,
+            None,
         )
         .unwrap_err();
         assert_eq!(err, ContractError::SoldOut);
     }
 }
+
+#[test]
+fn batch_minting_by_key() {
+    let mut deps = setup_test();
+
+    let (privkey, _, _) = mock_keys();
+
+    let claim = |owner: &str, signed_owner: &str| SignatureClaim {
+        owner: owner.to_string(),
+        signature: utils::sign(&privkey, &message(2, signed_owner)),
+        deadline: None,
+    };
+
+    // by default, the whole batch fails if any of the claims fails
+    {
+        let err = execute::batch_mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            2,
+            vec![claim("doge", "pumpkin"), claim("jake", "jake")],
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // skip failed claims and report them in the response data
+    {
+        let res = execute::batch_mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            2,
+            vec![
+                claim("jake", "jake"),
+                claim("doge", "pumpkin"),
+                claim("jake", "jake"),
+                claim("pumpkin", "pumpkin"),
+                claim("pepe", "pepe"),
+            ],
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/batch_mint_by_key"),
+                attr("id", "2"),
+                attr("minted", "2"),
+                attr("failed", "3"),
+            ],
+        );
+        assert_eq!(
+            from_binary::<BatchMintResponse>(&res.data.unwrap()).unwrap(),
+            BatchMintResponse {
+                minted: vec!["jake".to_string(), "pumpkin".to_string()],
+                failed: vec![
+                    FailedClaim {
+                        owner: "doge".to_string(),
+                        error: ContractError::InvalidSignature.to_string(),
+                    },
+                    FailedClaim {
+                        owner: "jake".to_string(),
+                        error: ContractError::already_claimed(2, "jake").to_string(),
+                    },
+                    FailedClaim {
+                        owner: "pepe".to_string(),
+                        error: ContractError::SoldOut.to_string(),
+                    },
+                ],
+            },
+        );
+
+        // current supply should have been updated
+        let badge = BADGES.load(deps.as_ref().storage, 2).unwrap();
+        assert_eq!(badge.current_supply, 100);
+
+        // failed owners should not be marked as already received
+        assert!(query::owner(deps.as_ref(), 2, "pumpkin").unwrap().claimed);
+        assert!(!query::owner(deps.as_ref(), 2, "doge").unwrap().claimed);
+    }
+}
+
+#[test]
+fn batch_minting_by_keys() {
+    let mut deps = setup_test();
+
+    let privkeys = (0..3).map(|_| utils::random_privkey()).collect::<Vec<_>>();
+    let pubkeys = privkeys
+        .iter()
+        .map(|privkey| hex::encode(VerifyingKey::from(privkey).to_bytes()))
+        .collect::<Vec<_>>();
+    for pubkey in &pubkeys {
+        KEYS.save(deps.as_mut().storage, (3, pubkey), &KeyInfo::new(KeyType::Secp256k1)).unwrap();
+    }
+
+    let claim = |idx: usize, owner: &str, signed_owner: &str| KeyClaim {
+        owner: owner.to_string(),
+        pubkey: pubkeys[idx].clone(),
+        signature: utils::sign(&privkeys[idx], &message(3, signed_owner)),
+        deadline: None,
+    };
+
+    // by default, the whole batch fails if any of the claims fails
+    {
+        let err = execute::batch_mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            vec![claim(0, "jake", "larry"), claim(1, "pumpkin", "pumpkin")],
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature);
+    }
+
+    // skip failed claims and report them in the response data
+    {
+        let res = execute::batch_mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            vec![
+                claim(0, "larry", "larry"),
+                claim(1, "jake", "pumpkin"),
+                claim(1, "pumpkin", "pumpkin"),
+                claim(2, "doge", "doge"),
+            ],
+            true,
+        )
+        .unwrap();
+
+        let expected_msg = |serial: u64, owner: &str| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: token_id(3, serial),
+                    owner: owner.to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })
+        };
+        assert_eq!(res.messages, vec![expected_msg(99, "larry"), expected_msg(100, "pumpkin")]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/batch_mint_by_keys"),
+                attr("id", "3"),
+                attr("minted", "2"),
+                attr("failed", "2"),
+            ],
+        );
+        assert_eq!(
+            from_binary::<BatchMintResponse>(&res.data.unwrap()).unwrap(),
+            BatchMintResponse {
+                minted: vec!["larry".to_string(), "pumpkin".to_string()],
+                failed: vec![
+                    FailedClaim {
+                        owner: "jake".to_string(),
+                        error: ContractError::InvalidSignature.to_string(),
+                    },
+                    FailedClaim {
+                        owner: "doge".to_string(),
+                        error: ContractError::SoldOut.to_string(),
+                    },
+                ],
+            },
+        );
+
+        // current supply should have been updated
+        let badge = BADGES.load(deps.as_ref().storage, 3).unwrap();
+        assert_eq!(badge.current_supply, 100);
+
+        // the keys of the successful claims should have been used, but not the failed ones
+        assert!(!query::key(deps.as_ref(), 3, &pubkeys[0]).unwrap().whitelisted);
+        assert!(!query::key(deps.as_ref(), 3, &pubkeys[1]).unwrap().whitelisted);
+        assert!(query::key(deps.as_ref(), 3, &pubkeys[2]).unwrap().whitelisted);
+
+        // failed owners should not be marked as already received
+        assert!(query::owner(deps.as_ref(), 3, "pumpkin").unwrap().claimed);
+        assert!(!query::owner(deps.as_ref(), 3, "jake").unwrap().claimed);
+    }
+}
+
+#[test]
+fn minting_by_multi_use_keys() {
+    let mut deps = setup_test();
+
+    let (privkey, _, pubkey_str) = mock_keys();
+
+    // the key can be used twice, before timestamp 11000, and stamps the "vip" tier
+    KEYS.save(
+        deps.as_mut().storage,
+        (3, &pubkey_str),
+        &KeyInfo {
+            key_type: KeyType::Secp256k1,
+            uses: 2,
+            expiry: Some(11000),
+            tier: Some("vip".to_string()),
+        },
+    )
+    .unwrap();
+
+    // properly mint using the key for the first time
+    {
+        let signature = utils::sign(&privkey, &message(3, "larry"));
+
+        let res = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+            pubkey_str.clone(),
+            signature,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_keys"),
+                attr("id", "3"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+
+        // the key should still be whitelisted, with one use remaining
+        let res = query::key(deps.as_ref(), 3, &pubkey_str).unwrap();
+        assert!(res.whitelisted);
+        assert_eq!(res.info.unwrap().uses, 1);
+
+        // the instance should have been stamped with the key's tier
+        let res = query::instance(deps.as_ref(), 3, 99).unwrap();
+        assert_eq!(res.tier, Some("vip".to_string()));
+    }
+
+    // attempt to mint after the key's expiry
+    {
+        let signature = utils::sign(&privkey, &message(3, "jake"));
+
+        let err = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(11001),
+            3,
+            "jake".to_string(),
+            pubkey_str.clone(),
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::key_expired(3));
+    }
+
+    // properly mint using the key for the second time
+    {
+        let signature = utils::sign(&privkey, &message(3, "jake"));
+
+        execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10500),
+            3,
+            "jake".to_string(),
+            pubkey_str.clone(),
+            signature,
+            None,
+        )
+        .unwrap();
+
+        // the key should have been used up and removed from the whitelist
+        let res = query::key(deps.as_ref(), 3, &pubkey_str).unwrap();
+        assert!(!res.whitelisted);
+        assert_eq!(res.info, None);
+
+        let res = query::instance(deps.as_ref(), 3, 100).unwrap();
+        assert_eq!(res.tier, Some("vip".to_string()));
+    }
+}
+
+#[test]
+fn minting_by_allowlist() {
+    let mut deps = setup_test();
+
+    BADGES
+        .update(deps.as_mut().storage, 3, |badge| {
+            let mut badge = badge.unwrap();
+            badge.rule = MintRule::ByAllowlist;
+            StdResult::Ok(badge)
+        })
+        .unwrap();
+
+    ALLOWLIST.insert(deps.as_mut().storage, (3, "larry")).unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::wrong_mint_rule("by_allowlist", &MintRule::by_minter("larry")),
+        );
+    }
+
+    // user not on the allowlist cannot claim
+    {
+        let err = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("jake"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::not_allowlisted(3, "jake"));
+    }
+
+    // user on the allowlist properly claims
+    {
+        let res = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("larry"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "3|99".to_string(),
+                    owner: "larry".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_allowlist"),
+                attr("id", "3"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+
+        let res = query::owner(deps.as_ref(), 3, "larry").unwrap();
+        assert!(res.claimed);
+    }
+
+    // user cannot claim again
+    {
+        let err = execute::mint_by_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(3, "larry"));
+    }
+
+    // under the generic mint message, the owner must claim the badge themselves
+    {
+        ALLOWLIST.insert(deps.as_mut().storage, (3, "jake")).unwrap();
+
+        let err = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &[]),
+            3,
+            "jake".to_string(),
+            vec![],
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotSelfClaim);
+
+        execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            3,
+            "jake".to_string(),
+            vec![],
+            None,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn minting_by_merkle_proof() {
+    let mut deps = setup_test();
+
+    let (root, proofs) = utils::merkle_tree(&["larry", "jake", "pumpkin"]);
+
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::ByMerkleRoot {
+                    root,
+                },
+                start: None,
+                expiry: Some(12345),
+                phases: vec![],
+                max_supply: Some(100),
+                current_supply: 98,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_merkle_proof(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+            proofs[0].clone(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_merkle_root", &MintRule::ByKeys));
+    }
+
+    // attempt to mint to an address that's not in the tree
+    {
+        let err = execute::mint_by_merkle_proof(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "doge".to_string(),
+            proofs[0].clone(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidMerkleProof);
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_merkle_proof(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            proofs[1].clone(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "jake".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_merkle_proof"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        // current supply should have been updated
+        let badge = BADGES.load(deps.as_ref().storage, 4).unwrap();
+        assert_eq!(badge.current_supply, 99);
+
+        // jake should be marked as already received
+        let res = query::owner(deps.as_ref(), 4, "jake").unwrap();
+        assert!(res.claimed);
+    }
+
+    // attempt to mint to the same user again
+    {
+        let err = execute::mint_by_merkle_proof(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            proofs[1].clone(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "jake"));
+    }
+
+    // attempt to mint after expiry
+    {
+        let err = execute::mint_by_merkle_proof(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            4,
+            "larry".to_string(),
+            proofs[0].clone(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Expired);
+    }
+
+    // attempt to mint after max supply is reached
+    {
+        set_badge_supply(deps.as_mut().storage, 4, 100);
+
+        let err = execute::mint_by_merkle_proof(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+            proofs[0].clone(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SoldOut);
+    }
+}
+
+#[test]
+fn minting_by_merkle_proof_with_amounts() {
+    let mut deps = setup_test();
+
+    let (root, proofs) = utils::merkle_tree(&["larry:2", "jake"]);
+
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::ByMerkleRoot {
+                    root,
+                },
+                start: None,
+                expiry: None,
+                phases: vec![],
+                max_supply: None,
+                current_supply: 0,
+                paused: false,
+                message_version: MessageVersion::V2,
+                max_per_user: None,
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+    fn mint(
+        deps: DepsMut,
+        owner: &str,
+        proof: &[String],
+        amount: Option<u64>,
+    ) -> Result<Response, ContractError> {
+        execute::mint_by_merkle_proof(
+            deps,
+            utils::mock_env_at_timestamp(10000),
+            4,
+            owner.to_string(),
+            proof.to_vec(),
+            amount,
+        )
+    }
+
+    // the proof can't be used without the amount committed to in the leaf
+    {
+        let err = mint(deps.as_mut(), "larry", &proofs[0], None).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMerkleProof);
+    }
+
+    // larry can claim as many times as the amount in his leaf
+    {
+        mint(deps.as_mut(), "larry", &proofs[0], Some(2)).unwrap();
+        mint(deps.as_mut(), "larry", &proofs[0], Some(2)).unwrap();
+
+        let err = mint(deps.as_mut(), "larry", &proofs[0], Some(2)).unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "larry"));
+    }
+
+    // a leaf without an amount can only be claimed once, even though there is no per-user limit
+    {
+        mint(deps.as_mut(), "jake", &proofs[1], None).unwrap();
+
+        let err = mint(deps.as_mut(), "jake", &proofs[1], None).unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "jake"));
+    }
+
+    let badge = BADGES.load(deps.as_ref().storage, 4).unwrap();
+    assert_eq!(badge.current_supply, 3);
+}
+
+#[test]
+fn minting_by_payment() {
+    let mut deps = setup_test();
+
+    FEE_RATE
+        .save(
+            deps.as_mut().storage,
+            &FeeRate {
+                metadata: Decimal::zero(),
+                key: Decimal::zero(),
+                mint: Decimal::zero(),
+            },
+        )
+        .unwrap();
+
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::ByPayment {
+                    price: coin(100, "uatom"),
+                    recipient: "larry".to_string(),
+                },
+                start: None,
+                expiry: Some(12345),
+                phases: vec![],
+                max_supply: Some(100),
+                current_supply: 98,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(100, "uatom")),
+            3,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_payment", &MintRule::ByKeys));
+    }
+
+    // attempt to mint with insufficient payment
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(99, "uatom")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::insufficient_payment(coin(100, "uatom"), coin(99, "uatom")),
+        );
+    }
+
+    // properly mint, with overpayment refunded to the sender
+    {
+        let res = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("pumpkin", &coins(120, "uatom")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "larry".to_string(),
+                    amount: coins(100, "uatom"),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "pumpkin".to_string(),
+                    amount: coins(20, "uatom"),
+                }),
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "nft".to_string(),
+                    msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(
+                        MintMsg::<Option<Empty>> {
+                            token_id: "4|99".to_string(),
+                            owner: "jake".to_string(),
+                            token_uri: None,
+                            extension: None,
+                        },
+                    ))
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_payment"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        // current supply should have been updated
+        let badge = BADGES.load(deps.as_ref().storage, 4).unwrap();
+        assert_eq!(badge.current_supply, 99);
+
+        // jake should be marked as already received
+        let res = query::owner(deps.as_ref(), 4, "jake").unwrap();
+        assert!(res.claimed);
+    }
+
+    // attempt to mint to the same user again
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &coins(100, "uatom")),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "jake"));
+    }
+
+    // attempt to mint after expiry
+    {
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            mock_info("larry", &coins(100, "uatom")),
+            4,
+            "larry".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Expired);
+    }
+
+    // attempt to mint after max supply is reached
+    {
+        set_badge_supply(deps.as_mut().storage, 4, 100);
+
+        let err = execute::mint_by_payment(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &coins(100, "uatom")),
+            4,
+            "larry".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SoldOut);
+    }
+}
+
+#[test]
+fn minting_by_prerequisites() {
+    let mut deps = setup_test();
+
+    deps.querier.update_wasm(utils::mock_nft(&[
+        ("larry", "1|1"),
+        ("larry", "2|5"),
+        ("jake", "1|2"),
+    ]));
+
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::ByPrerequisites {
+                    all_of: BTreeSet::from([1]),
+                    any_of: BTreeSet::from([2, 3]),
+                },
+                start: None,
+                expiry: Some(12345),
+                phases: vec![],
+                max_supply: Some(100),
+                current_supply: 98,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_prerequisites(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_prerequisites", &MintRule::ByKeys));
+    }
+
+    // attempt to mint to a user who doesn't hold the prerequisites
+    {
+        let err = execute::mint_by_prerequisites(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::no_prerequisite("jake"));
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_prerequisites(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "larry".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_prerequisites"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+
+        // current supply should have been updated
+        let badge = BADGES.load(deps.as_ref().storage, 4).unwrap();
+        assert_eq!(badge.current_supply, 99);
+
+        // larry should be marked as already received
+        let res = query::owner(deps.as_ref(), 4, "larry").unwrap();
+        assert!(res.claimed);
+    }
+
+    // attempt to mint to the same user again
+    {
+        let err = execute::mint_by_prerequisites(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "larry"));
+    }
+}
+
+#[test]
+fn minting_by_oracle() {
+    let mut deps = setup_test();
+
+    deps.querier.update_wasm(utils::mock_oracle(&["larry"]));
+
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::ByOracle {
+                    contract: "oracle".to_string(),
+                },
+                start: None,
+                expiry: Some(12345),
+                phases: vec![],
+                max_supply: Some(100),
+                current_supply: 98,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_oracle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_oracle", &MintRule::ByKeys));
+    }
+
+    // attempt to mint to a user who the oracle deems not eligible
+    {
+        let err = execute::mint_by_oracle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::not_eligible(4, "jake"));
+    }
+
+    // properly mint, with a proof
+    {
+        let res = execute::mint_by_oracle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            Some(Binary::from(b"ekaj")),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "jake".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_oracle"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "jake"),
+            ],
+        );
+
+        // current supply should have been updated
+        let badge = BADGES.load(deps.as_ref().storage, 4).unwrap();
+        assert_eq!(badge.current_supply, 99);
+
+        // jake should be marked as already received
+        let res = query::owner(deps.as_ref(), 4, "jake").unwrap();
+        assert!(res.claimed);
+    }
+
+    // attempt to mint to the same user again
+    {
+        let err = execute::mint_by_oracle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            Some(Binary::from(b"ekaj")),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "jake"));
+    }
+
+    // attempt to mint after max supply is reached
+    {
+        set_badge_supply(deps.as_mut().storage, 4, 100);
+
+        let err = execute::mint_by_oracle(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SoldOut);
+    }
+}
+
+#[test]
+fn minting_by_collection() {
+    let mut deps = setup_test();
+
+    deps.querier.update_wasm(utils::mock_nft(&[("larry", "69"), ("jake", "420")]));
+
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::ByCollection {
+                    collection: "collection".to_string(),
+                },
+                start: None,
+                expiry: Some(12345),
+                phases: vec![],
+                max_supply: Some(100),
+                current_supply: 98,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_collection(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+            "69".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_collection", &MintRule::ByKeys));
+    }
+
+    // attempt to mint using a token that the owner doesn't own
+    {
+        let err = execute::mint_by_collection(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+            "420".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::not_token_owner("420", "larry"));
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_collection(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+            "69".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "larry".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_collection"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+                attr("token_id", "69"),
+            ],
+        );
+
+        // current supply should have been updated
+        let badge = BADGES.load(deps.as_ref().storage, 4).unwrap();
+        assert_eq!(badge.current_supply, 99);
+
+        // larry should be marked as already received
+        let res = query::owner(deps.as_ref(), 4, "larry").unwrap();
+        assert!(res.claimed);
+
+        // the token should be marked as used
+        let res = query::collection_token(deps.as_ref(), 4, "69");
+        assert!(res.used);
+    }
+
+    // the token is transferred to another wallet, which attempts to mint with it again
+    {
+        deps.querier.update_wasm(utils::mock_nft(&[("jake", "69"), ("jake", "420")]));
+
+        let err = execute::mint_by_collection(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+            "69".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::token_used(4, "69"));
+    }
+}
+
+#[test]
+fn minting_by_balance() {
+    let mut deps = setup_test();
+
+    deps.querier.update_balance("larry", coins(100, "ustars"));
+    deps.querier.update_balance("jake", coins(99, "ustars"));
+
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::ByBalance {
+                    denom: Denom::Native("ustars".to_string()),
+                    amount: Uint128::new(100),
+                },
+                start: None,
+                expiry: Some(12345),
+                phases: vec![],
+                max_supply: Some(100),
+                current_supply: 98,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+    // wrong mint rule
+    {
+        let err = execute::mint_by_balance(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::wrong_mint_rule("by_balance", &MintRule::ByKeys));
+    }
+
+    // attempt to mint to a user with insufficient balance
+    {
+        let err = execute::mint_by_balance(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "jake".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::insufficient_balance("jake", Uint128::new(99), Uint128::new(100)),
+        );
+    }
+
+    // properly mint
+    {
+        let res = execute::mint_by_balance(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "larry".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint_by_balance"),
+                attr("id", "4"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+
+        // current supply should have been updated
+        let badge = BADGES.load(deps.as_ref().storage, 4).unwrap();
+        assert_eq!(badge.current_supply, 99);
+
+        // larry should be marked as already received
+        let res = query::owner(deps.as_ref(), 4, "larry").unwrap();
+        assert!(res.claimed);
+    }
+
+    // attempt to mint to the same user again
+    {
+        let err = execute::mint_by_balance(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            4,
+            "larry".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::already_claimed(4, "larry"));
+    }
+}
+
+#[test]
+fn minting_by_composite_rule() {
+    let mut deps = setup_test();
+
+    deps.querier.update_wasm(utils::mock_nft(&[("larry", "1|1"), ("pumpkin", "1|2")]));
+
+    let (privkey, _, pubkey_str) = mock_keys();
+    let (root, proofs) = utils::merkle_tree(&["jake", "pumpkin"]);
+
+    // badge 4 requires a whitelisted key AND holding badge 1
+    // badge 5 requires being in the merkle tree OR holding badge 1
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            4,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::All(vec![
+                    MintRule::ByKeys,
+                    MintRule::ByPrerequisites {
+                        all_of: BTreeSet::from([1]),
+                        any_of: BTreeSet::new(),
+                    },
+                ]),
+                start: None,
+                expiry: Some(12345),
+                phases: vec![],
+                max_supply: Some(100),
+                current_supply: 98,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+    BADGES
+        .save(
+            deps.as_mut().storage,
+            5,
+            &Badge {
+                manager: Some(Addr::unchecked("larry")),
+                metadata: Metadata::default(),
+                transferrable: true,
+                rule: MintRule::Any(vec![
+                    MintRule::ByMerkleRoot {
+                        root,
+                    },
+                    MintRule::ByPrerequisites {
+                        all_of: BTreeSet::from([1]),
+                        any_of: BTreeSet::new(),
+                    },
+                ]),
+                start: None,
+                expiry: Some(12345),
+                phases: vec![],
+                max_supply: Some(100),
+                current_supply: 98,
+                paused: false,
+                message_version: MessageVersion::Legacy,
+                max_per_user: Some(1),
+                require_consent: false,
+            },
+        )
+        .unwrap();
+
+    KEYS.save(deps.as_mut().storage, (4, &pubkey_str), &KeyInfo::new(KeyType::Secp256k1)).unwrap();
+
+    let key_proof = |owner: &str| MintProof::KeySignature {
+        pubkey: pubkey_str.clone(),
+        signature: utils::sign(&privkey, &message(4, owner)),
+    };
+    let merkle_proof = |proof: Vec<String>| MintProof::MerkleProof {
+        proof,
+        amount: None,
+    };
+
+    // the generic mint message also works for non-composite rules
+    {
+        let res = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            2,
+            "larry".to_string(),
+            vec![MintProof::Signature(utils::sign(&privkey, &message(2, "larry")))],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/mint"),
+                attr("id", "2"),
+                attr("serial", "99"),
+                attr("recipient", "larry"),
+            ],
+        );
+    }
+
+    // "all" rule: missing a proof
+    {
+        let err = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+            "larry".to_string(),
+            vec![],
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::missing_proof("key_signature"));
+    }
+
+    // "all" rule: has a valid key signature, but doesn't hold the prerequisite
+    {
+        let err = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+            "jake".to_string(),
+            vec![key_proof("jake")],
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::missing_prerequisite(1, "jake"));
+    }
+
+    // "all" rule: satisfies both sub-rules
+    {
+        let res = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+            "larry".to_string(),
+            vec![key_proof("larry")],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "nft".to_string(),
+                msg: to_binary(&sg721::ExecuteMsg::<_, Empty>::Mint(MintMsg::<Option<Empty>> {
+                    token_id: "4|99".to_string(),
+                    owner: "larry".to_string(),
+                    token_uri: None,
+                    extension: None,
+                }))
+                .unwrap(),
+                funds: vec![],
+            })],
+        );
+
+        // larry should be marked as already received
+        let res = query::owner(deps.as_ref(), 4, "larry").unwrap();
+        assert!(res.claimed);
+
+        // the pubkey should be removed from the whitelist
+        let res = query::key(deps.as_ref(), 4, &pubkey_str).unwrap();
+        assert!(!res.whitelisted);
+    }
+
+    // "all" rule: the key has been used
+    {
+        let err = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            4,
+            "pumpkin".to_string(),
+            vec![key_proof("pumpkin")],
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::key_does_not_exist(4));
+    }
+
+    // "any" rule: satisfies neither sub-rule
+    {
+        let err = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("doge", &[]),
+            5,
+            "doge".to_string(),
+            vec![merkle_proof(proofs[0].clone())],
+            None,
+        )
+        .unwrap_err();
+        // the error of the last sub-rule is returned
+        assert_eq!(err, ContractError::missing_prerequisite(1, "doge"));
+    }
+
+    // "any" rule: satisfies the first sub-rule
+    {
+        execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("jake", &[]),
+            5,
+            "jake".to_string(),
+            vec![merkle_proof(proofs[0].clone())],
+            None,
+        )
+        .unwrap();
+    }
+
+    // "any" rule: satisfies the second sub-rule, without providing a merkle proof. funds sent
+    // along are refunded, as no "by payment" rule takes effect
+    {
+        let res = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("larry", &coins(100, "ustars")),
+            5,
+            "larry".to_string(),
+            vec![],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(BankMsg::Send {
+                to_address: "larry".to_string(),
+                amount: coins(100, "ustars"),
+            }),
+        );
+
+        let badge = BADGES.load(deps.as_ref().storage, 5).unwrap();
+        assert_eq!(badge.current_supply, 100);
+    }
+
+    // "any" rule: sold out
+    {
+        let err = execute::mint(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            mock_info("pumpkin", &[]),
+            5,
+            "pumpkin".to_string(),
+            vec![merkle_proof(proofs[1].clone())],
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SoldOut);
+    }
+}
+
+#[test]
+fn minting_when_paused() {
+    let mut deps = setup_test();
+
+    let (privkey, _, pubkey_str) = mock_keys();
+
+    for id in 1..=3 {
+        set_badge_paused(deps.as_mut().storage, id, true);
+    }
+
+    // cannot mint by minter
+    {
+        let err = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Paused);
+    }
+
+    // cannot mint by key
+    {
+        let signature = utils::sign(&privkey, &message(2, "larry"));
+
+        let err = execute::mint_by_key(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            2,
+            "larry".to_string(),
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Paused);
+    }
+
+    // cannot mint by keys
+    {
+        let signature = utils::sign(&privkey, &message(3, "larry"));
+
+        let err = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+            pubkey_str.clone(),
+            signature.clone(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Paused);
+
+        // can mint once resumed
+        set_badge_paused(deps.as_mut().storage, 3, false);
+
+        execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "larry".to_string(),
+            pubkey_str,
+            signature,
+            None,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn minting_when_blocked() {
+    let mut deps = setup_test();
+
+    let (privkey, _, pubkey_str) = mock_keys();
+
+    // user blocks a badge
+    {
+        let res = execute::block_badge(deps.as_mut(), mock_info("jake", &[]), 1).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/block_badge"),
+                attr("id", "1"),
+                attr("user", "jake"),
+            ],
+        );
+
+        let res = query::blocked(deps.as_ref(), 1, "jake").unwrap();
+        assert!(res.blocked);
+
+        let res = query::blocked_badges(deps.as_ref(), "jake".to_string(), None, None).unwrap();
+        assert_eq!(res.ids, vec![1]);
+    }
+
+    // cannot mint a blocked badge to the user
+    {
+        let err = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake", "pumpkin"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::blocked(1, "jake"));
+    }
+
+    // can mint once unblocked
+    {
+        let res = execute::unblock_badge(deps.as_mut(), mock_info("jake", &[]), 1).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/unblock_badge"),
+                attr("id", "1"),
+                attr("user", "jake"),
+            ],
+        );
+
+        let res = query::blocked(deps.as_ref(), 1, "jake").unwrap();
+        assert!(!res.blocked);
+
+        execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["jake", "pumpkin"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap();
+    }
+
+    // user blocks a manager
+    {
+        let res =
+            execute::block_manager(deps.as_mut(), mock_info("pumpkin", &[]), "larry".to_string())
+                .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/block_manager"),
+                attr("manager", "larry"),
+                attr("user", "pumpkin"),
+            ],
+        );
+
+        let res = query::blocked(deps.as_ref(), 3, "pumpkin").unwrap();
+        assert!(res.blocked);
+
+        let res =
+            query::blocked_managers(deps.as_ref(), "pumpkin".to_string(), None, None).unwrap();
+        assert_eq!(res.managers, vec!["larry".to_string()]);
+    }
+
+    // cannot mint any badge of the blocked manager to the user
+    {
+        let signature = utils::sign(&privkey, &message(3, "pumpkin"));
+
+        let err = execute::mint_by_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            3,
+            "pumpkin".to_string(),
+            pubkey_str,
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::blocked(3, "pumpkin"));
+    }
+
+    // the minter of a "by minter" badge is blocked as well, even if they're not the manager
+    {
+        BADGES
+            .update(deps.as_mut().storage, 1, |badge| {
+                let mut badge = badge.unwrap();
+                badge.manager = Some(Addr::unchecked("doge"));
+                badge.current_supply = 0;
+                StdResult::Ok(badge)
+            })
+            .unwrap();
+
+        let res = query::blocked(deps.as_ref(), 1, "pumpkin").unwrap();
+        assert!(res.blocked);
+
+        let err = execute::mint_by_minter(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            utils::btreeset(&["pumpkin"]),
+            Addr::unchecked("larry"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::blocked(1, "pumpkin"));
+    }
+
+    // user unblocks the manager
+    {
+        execute::unblock_manager(deps.as_mut(), mock_info("pumpkin", &[]), "larry".to_string())
+            .unwrap();
+
+        let res =
+            query::blocked_managers(deps.as_ref(), "pumpkin".to_string(), None, None).unwrap();
+        assert_eq!(res.managers.len(), 0);
+    }
+}
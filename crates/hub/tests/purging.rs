@@ -1,11 +1,11 @@
 use cosmwasm_std::testing::{mock_dependencies,  MockApi, MockQuerier, MockStorage};
-use cosmwasm_std::{attr, Addr, Empty, OwnedDeps};
+use cosmwasm_std::{attr, Addr, Empty, OwnedDeps, StdResult};
 use sg_metadata::Metadata;
 
 use badge_hub::error::ContractError;
 use badge_hub::state::*;
 use badge_hub::{execute, query};
-use badges::{Badge, MintRule};
+use badges::{Badge, KeyInfo, KeyType, MessageVersion, MintRule, Phase};
 
 mod utils;
 
@@ -16,19 +16,37 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
         deps.as_mut().storage,
         1,
         &Badge {
-            manager: Addr::unchecked("larry"),
+            manager: Some(Addr::unchecked("larry")),
             metadata: Metadata::default(),
             transferrable: true,
             rule: MintRule::ByKeys,
+            start: None,
             expiry: Some(12345),
+            phases: vec![],
             max_supply: Some(100),
             current_supply: 2,
+            paused: false,
+            message_version: MessageVersion::Legacy,
+            max_per_user: Some(1),
+            require_consent: false,
         },
     )
     .unwrap();
 
-    KEYS.insert(deps.as_mut().storage, (1, "1234abcd")).unwrap();
-    KEYS.insert(deps.as_mut().storage, (1, "4321dcba")).unwrap();
+    KEYS.save(deps.as_mut().storage, (1, "1234abcd"), &KeyInfo::new(KeyType::Secp256k1)).unwrap();
+    KEYS.save(deps.as_mut().storage, (1, "4321dcba"), &KeyInfo::new(KeyType::Secp256k1)).unwrap();
+
+    ALLOWLIST.insert(deps.as_mut().storage, (1, "jake")).unwrap();
+    ALLOWLIST.insert(deps.as_mut().storage, (1, "pumpkin")).unwrap();
+
+    OFFERS.insert(deps.as_mut().storage, (1, "jake")).unwrap();
+    OFFERS.insert(deps.as_mut().storage, (1, "pumpkin")).unwrap();
+
+    COLLECTION_TOKENS.insert(deps.as_mut().storage, (1, "69")).unwrap();
+    COLLECTION_TOKENS.insert(deps.as_mut().storage, (1, "420")).unwrap();
+
+    NONCES.insert(deps.as_mut().storage, (1, 69)).unwrap();
+    NONCES.insert(deps.as_mut().storage, (1, 420)).unwrap();
 
     OWNERS.insert(deps.as_mut().storage, (1, "jake")).unwrap();
     OWNERS.insert(deps.as_mut().storage, (1, "pumpkin")).unwrap();
@@ -52,7 +70,27 @@ fn purging_keys() {
         assert_eq!(err, ContractError::Available);
     }
 
-    // can purge once the badge becomes unavailable
+    // cannot purge when the badge is merely paused
+    {
+        BADGES
+            .update(deps.as_mut().storage, 1, |opt| -> StdResult<_> {
+                let mut badge = opt.unwrap();
+                badge.paused = true;
+                Ok(badge)
+            })
+            .unwrap();
+
+        let err = execute::purge_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Available);
+    }
+
+    // can purge once the badge becomes unavailable, whether paused or not
     {
         let res = execute::purge_keys(
             deps.as_mut(),
@@ -96,6 +134,224 @@ fn purging_keys() {
     }
 }
 
+#[test]
+fn purging_keys_after_phases_sold_out() {
+    let mut deps = setup_test();
+
+    // the last phase is open-ended, but its supply cap has been reached
+    BADGES
+        .update(deps.as_mut().storage, 1, |opt| -> StdResult<_> {
+            let mut badge = opt.unwrap();
+            badge.expiry = None;
+            badge.phases = vec![
+                Phase {
+                    start: 5000,
+                    expiry: Some(7999),
+                    max_supply: Some(1),
+                },
+                Phase {
+                    start: 8000,
+                    expiry: None,
+                    max_supply: Some(3),
+                },
+            ];
+            Ok(badge)
+        })
+        .unwrap();
+
+    // cannot purge while the last phase still has supply left
+    {
+        let err = execute::purge_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Available);
+    }
+
+    // can purge once it has sold out, as no phase can mint any more
+    {
+        BADGES
+            .update(deps.as_mut().storage, 1, |opt| -> StdResult<_> {
+                let mut badge = opt.unwrap();
+                badge.current_supply = 3;
+                Ok(badge)
+            })
+            .unwrap();
+
+        let res = execute::purge_keys(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            None,
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("keys_purged", "2")));
+    }
+}
+
+#[test]
+fn purging_allowlist() {
+    let mut deps = setup_test();
+
+    // cannot purge when the badge is available
+    {
+        let err = execute::purge_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Available);
+    }
+
+    // can purge once the badge becomes unavailable
+    {
+        let res = execute::purge_allowlist(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            1,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/purge_allowlist"),
+                attr("id", "1"),
+                attr("users_purged", "2"),
+            ],
+        );
+
+        let res = query::allowlist(deps.as_ref(), 1, None, None).unwrap();
+        assert_eq!(res.users.len(), 0);
+    }
+}
+
+#[test]
+fn purging_offers() {
+    let mut deps = setup_test();
+
+    // cannot purge when the badge is available
+    {
+        let err = execute::purge_offers(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Available);
+    }
+
+    // can purge once the badge becomes unavailable
+    {
+        let res = execute::purge_offers(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            1,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/purge_offers"),
+                attr("id", "1"),
+                attr("offers_purged", "2"),
+            ],
+        );
+
+        let res = query::offers(deps.as_ref(), 1, None, None).unwrap();
+        assert_eq!(res.users.len(), 0);
+    }
+}
+
+#[test]
+fn purging_collection_tokens() {
+    let mut deps = setup_test();
+
+    // cannot purge when the badge is available
+    {
+        let err = execute::purge_collection_tokens(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Available);
+    }
+
+    // can purge once the badge becomes unavailable
+    {
+        let res = execute::purge_collection_tokens(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            1,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/purge_collection_tokens"),
+                attr("id", "1"),
+                attr("tokens_purged", "2"),
+            ],
+        );
+
+        assert!(!query::collection_token(deps.as_ref(), 1, "69").used);
+        assert!(!query::collection_token(deps.as_ref(), 1, "420").used);
+    }
+}
+
+#[test]
+fn purging_nonces() {
+    let mut deps = setup_test();
+
+    // cannot purge when the badge is available
+    {
+        let err = execute::purge_nonces(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(10000),
+            1,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Available);
+    }
+
+    // can purge once the badge becomes unavailable
+    {
+        let res = execute::purge_nonces(
+            deps.as_mut(),
+            utils::mock_env_at_timestamp(99999),
+            1,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "badges/hub/purge_nonces"),
+                attr("id", "1"),
+                attr("nonces_purged", "2"),
+            ],
+        );
+
+        assert!(!query::voucher(deps.as_ref(), 1, 69).used);
+        assert!(!query::voucher(deps.as_ref(), 1, 420).used);
+    }
+}
+
 #[test]
 fn purging_owners() {
     let mut deps = setup_test();
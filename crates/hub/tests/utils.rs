@@ -3,9 +3,18 @@
 use std::collections::BTreeSet;
 
 use cosmwasm_std::testing::mock_env;
-use cosmwasm_std::{Env, Timestamp};
-use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+use badge_hub::helpers::{hash, hash_pair};
+use badges::{nft, oracle};
+use cosmwasm_std::{
+    from_slice, to_binary, Binary, ContractResult, Env, QuerierResult, SystemError, SystemResult,
+    Timestamp, Uint128, WasmQuery,
+};
+use ed25519_zebra::{SigningKey as Ed25519SigningKey, VerificationKey as Ed25519VerificationKey};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{recoverable, Signature, SigningKey, VerifyingKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
 use rand::rngs::OsRng;
+use sha3::{Digest, Keccak256};
 
 pub const MOCK_PRIVKEY: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
 
@@ -27,6 +36,37 @@ pub fn sign(privkey: &SigningKey, msg: &str) -> String {
     hex::encode(sig_bytes)
 }
 
+/// Generate a random Ed25519 private key, and return it along with the hex-encoded pubkey
+pub fn random_ed25519_privkey() -> (Ed25519SigningKey, String) {
+    let privkey = Ed25519SigningKey::new(OsRng);
+    let pubkey_bytes: [u8; 32] = Ed25519VerificationKey::from(&privkey).into();
+    (privkey, hex::encode(pubkey_bytes))
+}
+
+/// Sign a message using the provided Ed25519 privkey, and encode the signature in hex
+pub fn sign_ed25519(privkey: &Ed25519SigningKey, msg: &str) -> String {
+    let sig_bytes: [u8; 64] = privkey.sign(msg.as_bytes()).into();
+    hex::encode(sig_bytes)
+}
+
+/// The hex-encoded Ethereum address controlled by the provided privkey
+pub fn eth_address(privkey: &SigningKey) -> String {
+    let pubkey = VerifyingKey::from(privkey).to_encoded_point(false);
+    let pubkey_hash = Keccak256::digest(&pubkey.as_bytes()[1..]);
+    hex::encode(&pubkey_hash[12..])
+}
+
+/// Sign a message in the way of an Ethereum wallet's `personal_sign` method, and encode the
+/// signature in hex, with the recovery id being 27 or 28
+pub fn sign_eip191(privkey: &SigningKey, msg: &str) -> String {
+    // the recoverable signature uses keccak256 as the hash function
+    let prefixed_msg = format!("\x19Ethereum Signed Message:\n{}{}", msg.len(), msg);
+    let sig: recoverable::Signature = privkey.sign(prefixed_msg.as_bytes());
+    let mut sig_bytes = sig.as_ref().to_vec();
+    sig_bytes[64] += 27;
+    hex::encode(sig_bytes)
+}
+
 /// Cast a slice of strings into a btreeset
 pub fn btreeset(slice: &[&str]) -> BTreeSet<String> {
     slice.iter().map(|s| s.to_string()).collect()
@@ -38,3 +78,144 @@ pub fn mock_env_at_timestamp(timestamp: u64) -> Env {
     env.block.time = Timestamp::from_seconds(timestamp);
     env
 }
+
+/// Build a Merkle tree whose leaves are the hashes of the provided addresses. Return the
+/// hex-encoded root, and the hex-encoded proof for each of the addresses.
+pub fn merkle_tree(addrs: &[&str]) -> (String, Vec<Vec<String>>) {
+    let mut layer: Vec<Vec<u8>> = addrs.iter().map(|addr| hash(addr)).collect();
+    let mut proofs: Vec<Vec<String>> = vec![vec![]; addrs.len()];
+    let mut positions: Vec<usize> = (0..addrs.len()).collect();
+
+    while layer.len() > 1 {
+        for (proof, pos) in proofs.iter_mut().zip(positions.iter_mut()) {
+            // a node without a sibling is promoted to the next layer as is
+            let sibling = *pos ^ 1;
+            if sibling < layer.len() {
+                proof.push(hex::encode(&layer[sibling]));
+            }
+            *pos /= 2;
+        }
+
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair(a, b),
+                [a] => a.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    (hex::encode(&layer[0]), proofs)
+}
+
+/// The result returned by the mock queriers for queries they don't support
+fn unsupported(kind: &str) -> QuerierResult {
+    SystemResult::Err(SystemError::UnsupportedRequest {
+        kind: kind.to_string(),
+    })
+}
+
+/// Return a handler for wasm queries that mocks an NFT contract's `owner_of` and `tokens` queries,
+/// where `tokens` is a list of (owner, token_id) pairs
+pub fn mock_nft(tokens: &[(&str, &str)]) -> impl Fn(&WasmQuery) -> QuerierResult {
+    let mut tokens: Vec<(String, String)> =
+        tokens.iter().map(|(owner, token_id)| (owner.to_string(), token_id.to_string())).collect();
+    tokens.sort_by(|a, b| a.1.cmp(&b.1));
+
+    move |query| match query {
+        WasmQuery::Smart {
+            msg,
+            ..
+        } => match from_slice(msg).unwrap() {
+            nft::QueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+            } => {
+                let res = nft::TokensResponse {
+                    tokens: tokens
+                        .iter()
+                        .filter(|(o, _)| *o == owner)
+                        .map(|(_, token_id)| token_id.clone())
+                        .filter(|token_id| !matches!(&start_after, Some(sa) if token_id <= sa))
+                        .take(limit.unwrap_or(10) as usize)
+                        .collect(),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            },
+            nft::QueryMsg::OwnerOf {
+                token_id,
+                ..
+            } => match tokens.iter().find(|(_, t)| *t == token_id) {
+                Some((owner, _)) => {
+                    let res = nft::OwnerOfResponse {
+                        owner: owner.clone(),
+                        approvals: vec![],
+                    };
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+                },
+                None => SystemResult::Ok(ContractResult::Err("token not found".to_string())),
+            },
+            _ => unsupported("nft query"),
+        },
+        _ => unsupported("non-smart wasm query"),
+    }
+}
+
+/// Return a handler for wasm queries that mocks an eligibility oracle, which deems a user eligible
+/// if the user is in the provided list, or if the proof is the user's name spelled backwards
+pub fn mock_oracle(eligible_users: &[&str]) -> impl Fn(&WasmQuery) -> QuerierResult {
+    let eligible_users: Vec<String> = eligible_users.iter().map(|user| user.to_string()).collect();
+
+    move |query| match query {
+        WasmQuery::Smart {
+            msg,
+            ..
+        } => match from_slice(msg).unwrap() {
+            oracle::QueryMsg::IsEligible {
+                user,
+                proof,
+                ..
+            } => {
+                let reversed: String = user.chars().rev().collect();
+                let res = oracle::IsEligibleResponse {
+                    eligible: eligible_users.contains(&user)
+                        || proof == Some(Binary::from(reversed.as_bytes())),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            },
+        },
+        _ => unsupported("non-smart wasm query"),
+    }
+}
+
+/// Return a handler for wasm queries that mocks a cw20 token contract's `balance` query, where
+/// `balances` is a list of (user, balance) pairs
+pub fn mock_cw20(balances: &[(&str, u128)]) -> impl Fn(&WasmQuery) -> QuerierResult {
+    let balances: Vec<(String, u128)> =
+        balances.iter().map(|(user, balance)| (user.to_string(), *balance)).collect();
+
+    move |query| match query {
+        WasmQuery::Smart {
+            msg,
+            ..
+        } => match from_slice(msg).unwrap() {
+            cw20::Cw20QueryMsg::Balance {
+                address,
+            } => {
+                let balance = balances
+                    .iter()
+                    .find(|(user, _)| *user == address)
+                    .map(|(_, balance)| *balance)
+                    .unwrap_or(0);
+                let res = cw20::BalanceResponse {
+                    balance: Uint128::new(balance),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            },
+            _ => unsupported("cw20 query"),
+        },
+        _ => unsupported("non-smart wasm query"),
+    }
+}
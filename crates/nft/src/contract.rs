@@ -6,7 +6,7 @@ use cw721::Cw721Query;
 use sg_metadata::{Metadata, Trait};
 use sg_std::Response;
 
-use badges::hub::BadgeResponse;
+use badges::hub::{BadgeResponse, InstanceResponse};
 use badges::nft::{AllNftInfoResponse, Extension, InstantiateMsg, NftInfoResponse};
 
 use crate::state::API_URL;
@@ -44,15 +44,23 @@ impl<'a> NftContract<'a> {
         )
     }
 
-    /// Assert that the badge is transferrable
+    /// Assert that the badge is transferrable, and the instance has not been revoked
     pub fn assert_transferrable(&self, deps: Deps, token_id: impl ToString) -> StdResult<()> {
-        let (id, _) = parse_token_id(&token_id.to_string())?;
+        let (id, serial) = parse_token_id(&token_id.to_string())?;
         let badge = self.query_badge(deps, id)?;
-        if badge.transferrable {
-            Ok(())
-        } else {
-            Err(StdError::generic_err(format!("badge {} is not transferrable", id)))
+        if !badge.transferrable {
+            return Err(StdError::generic_err(format!("badge {} is not transferrable", id)));
+        }
+
+        let instance = self.query_instance(deps, id, serial)?;
+        if instance.revoked {
+            return Err(StdError::generic_err(format!(
+                "instance {} of badge {} has been revoked",
+                serial, id
+            )));
         }
+
+        Ok(())
     }
 
     /// Overrides vanilla cw721's `nft_info` method
@@ -60,9 +68,10 @@ impl<'a> NftContract<'a> {
         let (id, serial) = parse_token_id(&token_id.to_string())?;
         let uri = uri(deps.storage, id, serial)?;
         let badge = self.query_badge(deps, id)?;
+        let instance = self.query_instance(deps, id, serial)?;
         Ok(NftInfoResponse {
             token_uri: Some(uri),
-            extension: prepend_traits(badge.metadata, id, serial),
+            extension: prepend_traits(badge.metadata, instance),
         })
     }
 
@@ -99,6 +108,19 @@ impl<'a> NftContract<'a> {
             },
         )
     }
+
+    /// Query the Hub contract for the data specific to a badge instance, i.e. its tier and whether
+    /// it has been revoked
+    fn query_instance(&self, deps: Deps, id: u64, serial: u64) -> StdResult<InstanceResponse> {
+        let minter = self.parent.parent.minter(deps)?;
+        deps.querier.query_wasm_smart(
+            &minter.minter,
+            &badges::hub::QueryMsg::Instance {
+                id,
+                serial,
+            },
+        )
+    }
 }
 
 /// URL of an API serving the metadata of the NFT.
@@ -129,22 +151,47 @@ pub fn parse_token_id(token_id: &str) -> StdResult<(u64, u64)> {
     Ok((id, serial))
 }
 
-/// The badge's id and serial are prepended to it's list of traits.
-pub fn prepend_traits(mut metadata: Metadata, id: u64, serial: u64) -> Metadata {
+/// The badge's id and serial, as well as the instance's tier if it has one and its revocation if
+/// it has been revoked, are prepended to it's list of traits.
+pub fn prepend_traits(mut metadata: Metadata, instance: InstanceResponse) -> Metadata {
     let mut traits = vec![
         Trait {
             display_type: None,
             trait_type: "id".to_string(),
-            value: id.to_string(),
+            value: instance.id.to_string(),
         },
         Trait {
             display_type: None,
             trait_type: "serial".to_string(),
-            value: serial.to_string(),
+            value: instance.serial.to_string(),
         },
     ];
 
-    traits.extend(metadata.attributes.unwrap_or_default().into_iter());
+    if let Some(tier) = instance.tier {
+        traits.push(Trait {
+            display_type: None,
+            trait_type: "tier".to_string(),
+            value: tier,
+        });
+    }
+
+    if instance.revoked {
+        traits.push(Trait {
+            display_type: None,
+            trait_type: "revoked".to_string(),
+            value: "true".to_string(),
+        });
+    }
+
+    if let Some(reason) = instance.revocation_reason {
+        traits.push(Trait {
+            display_type: None,
+            trait_type: "revocation_reason".to_string(),
+            value: reason,
+        });
+    }
+
+    traits.extend(metadata.attributes.unwrap_or_default());
 
     metadata.attributes = Some(traits);
     metadata
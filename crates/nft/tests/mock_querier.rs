@@ -57,6 +57,8 @@ impl Querier for CustomQuerier {
 pub struct HubQuerier {
     contract_addr: Addr,
     badges: HashMap<u64, Badge>,
+    tiers: HashMap<(u64, u64), String>,
+    revocations: HashMap<(u64, u64), Option<String>>,
 }
 
 impl Default for HubQuerier {
@@ -64,6 +66,8 @@ impl Default for HubQuerier {
         HubQuerier {
             contract_addr: Addr::unchecked("hub"),
             badges: HashMap::default(),
+            tiers: HashMap::default(),
+            revocations: HashMap::default(),
         }
     }
 }
@@ -73,6 +77,14 @@ impl HubQuerier {
         self.badges.insert(id, badge);
     }
 
+    pub fn set_tier(&mut self, id: u64, serial: u64, tier: impl Into<String>) {
+        self.tiers.insert((id, serial), tier.into());
+    }
+
+    pub fn set_revoked(&mut self, id: u64, serial: u64, reason: Option<&str>) {
+        self.revocations.insert((id, serial), reason.map(String::from));
+    }
+
     pub fn handle_query(&self, contract_addr: &Addr, msg: hub::QueryMsg) -> QuerierResult {
         if contract_addr != &self.contract_addr {
             panic!(
@@ -94,6 +106,20 @@ impl HubQuerier {
                 Ok(to_binary(&res).into()).into()
             },
 
+            hub::QueryMsg::Instance {
+                id,
+                serial,
+            } => {
+                let res = hub::InstanceResponse {
+                    id,
+                    serial,
+                    tier: self.tiers.get(&(id, serial)).cloned(),
+                    revoked: self.revocations.contains_key(&(id, serial)),
+                    revocation_reason: self.revocations.get(&(id, serial)).cloned().flatten(),
+                };
+                Ok(to_binary(&res).into()).into()
+            },
+
             _ => panic!("[mock]: unsupported hub query: {:?}", msg),
         }
     }
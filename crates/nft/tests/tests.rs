@@ -10,8 +10,9 @@ use sg721::CollectionInfo;
 use sg_metadata::{Metadata, Trait};
 
 use badge_nft::contract::{parse_token_id, prepend_traits, NftContract};
+use badges::hub::InstanceResponse;
 use badges::nft::{ExecuteMsg, Extension, InstantiateMsg};
-use badges::{Badge, MintRule};
+use badges::{Badge, MessageVersion, MintRule};
 
 mod mock_querier;
 
@@ -29,6 +30,16 @@ fn mock_metadata() -> Metadata {
     }
 }
 
+fn mock_instance(id: u64, serial: u64) -> InstanceResponse {
+    InstanceResponse {
+        id,
+        serial,
+        tier: None,
+        revoked: false,
+        revocation_reason: None,
+    }
+}
+
 fn setup_test() -> OwnedDeps<MockStorage, MockApi, mock_querier::CustomQuerier, Empty> {
     let mut deps = OwnedDeps {
         storage: MockStorage::default(),
@@ -40,26 +51,38 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, mock_querier::CustomQuerier,
     deps.querier.hub.set_badge(
         69,
         Badge {
-            manager: Addr::unchecked("larry"),
+            manager: Some(Addr::unchecked("larry")),
             metadata: mock_metadata(),
             transferrable: true,
             rule: MintRule::ByKeys,
+            start: None,
             expiry: None,
+            phases: vec![],
             max_supply: None,
             current_supply: 420,
+            paused: false,
+            message_version: MessageVersion::Legacy,
+            max_per_user: Some(1),
+            require_consent: false,
         },
     );
 
     deps.querier.hub.set_badge(
         420,
         Badge {
-            manager: Addr::unchecked("jake"),
+            manager: Some(Addr::unchecked("jake")),
             metadata: mock_metadata(),
             transferrable: false,
             rule: MintRule::ByKeys,
+            start: None,
             expiry: None,
+            phases: vec![],
             max_supply: None,
             current_supply: 88888,
+            paused: false,
+            message_version: MessageVersion::Legacy,
+            max_per_user: Some(1),
+            require_consent: false,
         },
     );
 
@@ -151,7 +174,69 @@ fn parsing_token_id() {
 
 #[test]
 fn prepending_traits() {
-    let metadata = prepend_traits(mock_metadata(), 69, 420);
+    let metadata = prepend_traits(mock_metadata(), mock_instance(69, 420));
+    assert_eq!(
+        metadata.attributes.unwrap(),
+        vec![
+            Trait {
+                display_type: None,
+                trait_type: "id".to_string(),
+                value: "69".to_string(),
+            },
+            Trait {
+                display_type: None,
+                trait_type: "serial".to_string(),
+                value: "420".to_string(),
+            },
+            Trait {
+                display_type: None,
+                trait_type: "rarity".to_string(),
+                value: "SSR".to_string(),
+            },
+        ]
+    );
+
+    let metadata = prepend_traits(
+        mock_metadata(),
+        InstanceResponse {
+            tier: Some("vip".to_string()),
+            ..mock_instance(69, 420)
+        },
+    );
+    assert_eq!(
+        metadata.attributes.unwrap(),
+        vec![
+            Trait {
+                display_type: None,
+                trait_type: "id".to_string(),
+                value: "69".to_string(),
+            },
+            Trait {
+                display_type: None,
+                trait_type: "serial".to_string(),
+                value: "420".to_string(),
+            },
+            Trait {
+                display_type: None,
+                trait_type: "tier".to_string(),
+                value: "vip".to_string(),
+            },
+            Trait {
+                display_type: None,
+                trait_type: "rarity".to_string(),
+                value: "SSR".to_string(),
+            },
+        ]
+    );
+
+    let metadata = prepend_traits(
+        mock_metadata(),
+        InstanceResponse {
+            revoked: true,
+            revocation_reason: Some("cheating".to_string()),
+            ..mock_instance(69, 420)
+        },
+    );
     assert_eq!(
         metadata.attributes.unwrap(),
         vec![
@@ -165,6 +250,16 @@ fn prepending_traits() {
                 trait_type: "serial".to_string(),
                 value: "420".to_string(),
             },
+            Trait {
+                display_type: None,
+                trait_type: "revoked".to_string(),
+                value: "true".to_string(),
+            },
+            Trait {
+                display_type: None,
+                trait_type: "revocation_reason".to_string(),
+                value: "cheating".to_string(),
+            },
             Trait {
                 display_type: None,
                 trait_type: "rarity".to_string(),
@@ -235,16 +330,52 @@ fn rejecting_transfers() {
     // sg721_base::ContractError does not implement Eq or PartialEq, so we can't directly compare
     // the error types here
     assert_eq!(err.to_string(), "Generic error: badge 420 is not transferrable");
+
+    // attempt to transfer a revoked token, should fail
+    deps.querier.hub.set_revoked(69, 420, None);
+
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pumpkin", &[]),
+        ExecuteMsg::TransferNft {
+            recipient: "jake".to_string(),
+            token_id: "69|420".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: instance 420 of badge 69 has been revoked");
 }
 
 #[test]
 fn querying_nft_info() {
-    let deps = setup_test();
+    let mut deps = setup_test();
     let contract = NftContract::default();
 
     let info = contract.nft_info(deps.as_ref(), "69|420").unwrap();
     assert_eq!(info.token_uri.unwrap(), "https://badges-api.larry.engineer/metadata?id=69&serial=420");
-    assert_eq!(info.extension, prepend_traits(mock_metadata(), 69, 420));
+    assert_eq!(info.extension, prepend_traits(mock_metadata(), mock_instance(69, 420)));
+
+    // an instance minted using a tiered key has the tier trait
+    deps.querier.hub.set_tier(69, 420, "vip");
+
+    let info = contract.nft_info(deps.as_ref(), "69|420").unwrap();
+    let instance = InstanceResponse {
+        tier: Some("vip".to_string()),
+        ..mock_instance(69, 420)
+    };
+    assert_eq!(info.extension, prepend_traits(mock_metadata(), instance.clone()));
+
+    // a revoked instance has the revoked trait
+    deps.querier.hub.set_revoked(69, 420, Some("cheating"));
+
+    let info = contract.nft_info(deps.as_ref(), "69|420").unwrap();
+    let instance = InstanceResponse {
+        revoked: true,
+        revocation_reason: Some("cheating".to_string()),
+        ..instance
+    };
+    assert_eq!(info.extension, prepend_traits(mock_metadata(), instance));
 }
 
 #[test]
@@ -258,5 +389,5 @@ fn querying_all_nft_info() {
     } = contract.all_nft_info(deps.as_ref(), mock_env(), "69|420".to_string(), None).unwrap();
     assert_eq!(access.owner, "jake");
     assert_eq!(info.token_uri.unwrap(), "https://badges-api.larry.engineer/metadata?id=69&serial=420");
-    assert_eq!(info.extension, prepend_traits(mock_metadata(), 69, 420));
+    assert_eq!(info.extension, prepend_traits(mock_metadata(), mock_instance(69, 420)));
 }
@@ -38,6 +38,11 @@ const hrStyle = {
   width: "calc(50% - 0.5rem - 9px)",
 };
 
+type ByKeyRule = {
+  key: string;
+  key_type: string;
+};
+
 const fillerImageUrl = "https://via.placeholder.com/500?text=Image+Not+Available";
 const fillerText = "Undefined";
 
@@ -234,7 +239,10 @@ export default function Claim() {
             );
           });
       } else if ("by_key" in badge.rule) {
-        if (pubkeyStr === badge.rule["by_key"]) {
+        // since v1.3, the "by key" rule specifies the key's type along with the key itself; the
+        // published typings still describe the key as a plain string
+        const { key, key_type } = badge.rule["by_key"] as unknown as ByKeyRule;
+        if (key_type === "secp256k1" && pubkeyStr === key) {
           return setPrivkeyValidTrue();
         } else {
           return setPrivkeyValidFalse(`this key is not eligible to claim badge #${idStr}`);
@@ -363,7 +371,7 @@ export default function Claim() {
 
   async function getMintMsg() {
     const privKey = Buffer.from(privkeyStr, "hex");
-    const msg = `claim badge ${idStr} for user ${owner}`;
+    const msg = await store.getClaimMessage(Number(idStr), owner);
     const msgBytes = Buffer.from(msg, "utf8");
     const msgHashBytes = sha256(msgBytes);
     const { signature } = secp256k1.ecdsaSign(msgHashBytes, privKey);
@@ -11,6 +11,12 @@ import {
 import create from "zustand";
 import { Network, NetworkConfig, NETWORK_CONFIGS, PUBLIC_ACCOUNTS } from "./configs";
 
+// not yet included in the published typings
+export type ClaimMessageResponse = {
+  version: "legacy" | "v2";
+  message: string;
+};
+
 export type State = {
   networkConfig?: NetworkConfig;
 
@@ -26,6 +32,7 @@ export type State = {
   getBadge: (id: number) => Promise<BadgeResponse>;
   isKeyWhitelisted: (id: number, privkeyStr: string) => Promise<boolean>;
   isOwnerEligible: (id: number, owner: string) => Promise<boolean>;
+  getClaimMessage: (id: number, owner: string) => Promise<string>;
 };
 
 export const useStore = create<State>((set) => ({
@@ -127,4 +134,18 @@ export const useStore = create<State>((set) => ({
     );
     return !ownerRes.claimed; // the address is eligible if it has NOT claimed
   },
+
+  // the format of the message depends on the badge, so we let the contract compose it
+  getClaimMessage: async function (id: number, owner: string) {
+    const claimMessageRes: ClaimMessageResponse = await this.wasmClient!.queryContractSmart(
+      this.networkConfig!.hub,
+      {
+        claim_message: {
+          id,
+          user: owner,
+        },
+      }
+    );
+    return claimMessageRes.message;
+  },
 }));